@@ -0,0 +1,16 @@
+//! Helpers shared by the command-line tools.
+
+use anyhow::{Context, Result};
+use std::str::FromStr;
+
+/// Parse a command-line argument, saying `what` it was meant to be if it
+/// can't be parsed.
+pub fn parse_arg<T>(s: &str, what: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    s.parse::<T>()
+        .map_err(Into::into)
+        .with_context(|| format!("Could not parse {} `{}`", what, s))
+}
@@ -0,0 +1,562 @@
+//! Luggage rules, as used on day 7.
+//!
+//! A rules file has one rule per line, saying which bags, and how many of
+//! each, a bag of some color must contain:
+//!
+//! ```text
+//! light red bags contain 1 bright white bag, 2 muted yellow bags.
+//! faded blue bags contain no other bags.
+//! ```
+
+pub mod export;
+pub mod rule;
+
+use anyhow::{anyhow, Context, Result};
+use rule::Rule;
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    fmt::{self, Display},
+};
+
+/// A bag color, interned as an index into [`BagRules`].
+pub type ColorId = usize;
+
+/// One edge of the rules graph.
+///
+/// In [`BagRules::contents`], `color` is the bag that is contained, and in
+/// [`BagRules::containers`] it's the bag that contains. Either way, `count` is
+/// how many of the contained bag there are.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Edge {
+    pub color: ColorId,
+    pub count: usize,
+}
+
+/// A chain of bags, each directly containing the next.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BagPath {
+    /// The outermost bag
+    pub start: ColorId,
+    /// The edge to each bag after the first, from outermost to innermost
+    pub edges: Vec<Edge>,
+}
+
+impl BagPath {
+    /// Every color along the path, from outermost to innermost.
+    pub fn colors(&self) -> impl Iterator<Item = ColorId> + '_ {
+        Some(self.start)
+            .into_iter()
+            .chain(self.edges.iter().map(|edge| edge.color))
+    }
+
+    /// How many of the innermost bag the outermost bag holds along this
+    /// path, or `None` if that doesn't fit in a `usize`.
+    pub fn multiplicity(&self) -> Option<usize> {
+        self.edges
+            .iter()
+            .try_fold(1usize, |product, edge| product.checked_mul(edge.count))
+    }
+}
+
+/// The rules as a graph, with an edge from each bag to every bag it must
+/// contain and back again.
+#[derive(Debug, Default, Clone)]
+pub struct BagRules {
+    colors: Vec<String>,
+    ids: HashMap<String, ColorId>,
+    contents: Vec<Vec<Edge>>,
+    containers: Vec<Vec<Edge>>,
+    /// Whether each color has a rule of its own, rather than only being
+    /// contained by other bags
+    has_rule: Vec<bool>,
+    /// Colors with a rule, in the order the rules were added
+    rule_order: Vec<ColorId>,
+}
+
+impl BagRules {
+    /// Parse a rules file, and check the rules with [`BagRules::validate`].
+    /// Blank lines are ignored.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut rules = Self::default();
+        let mut rule_lines = HashMap::new();
+
+        for (line_num, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let rule = line
+                .parse::<Rule>()
+                .with_context(|| format!("Could not parse rule on line {}", line_num + 1))?;
+
+            if let Some(first_line) = rules.id(&rule.color).and_then(|id| rule_lines.get(&id)) {
+                return Err(anyhow!(
+                    "Duplicate rule for \"{}\" on line {}, first given on line {}",
+                    rule.color,
+                    line_num + 1,
+                    first_line
+                ));
+            }
+
+            let id = rules.add_rule(&rule.color, &rule.contents)?;
+            rule_lines.insert(id, line_num + 1);
+        }
+
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    /// Add a rule saying that a bag of `color` must contain the bags in
+    /// `contents`, and return the color's id. It's an error for a color to
+    /// have more than one rule.
+    ///
+    /// Rules added this way aren't checked for undefined colors or cycles
+    /// until [`BagRules::validate`] is called.
+    pub fn add_rule<S: AsRef<str>>(
+        &mut self,
+        color: &str,
+        contents: &[(usize, S)],
+    ) -> Result<ColorId> {
+        let id = self.intern(color);
+
+        if self.has_rule[id] {
+            return Err(anyhow!("Bag \"{}\" already has a rule", color));
+        }
+
+        self.has_rule[id] = true;
+        self.rule_order.push(id);
+
+        for (count, inner) in contents {
+            let inner = self.intern(inner.as_ref());
+
+            self.contents[id].push(Edge {
+                color: inner,
+                count: *count,
+            });
+            self.containers[inner].push(Edge {
+                color: id,
+                count: *count,
+            });
+        }
+
+        Ok(id)
+    }
+
+    /// Check that every color that's contained by another bag has a rule of
+    /// its own, and that no bag eventually contains itself.
+    pub fn validate(&self) -> Result<()> {
+        let undefined = (0..self.len())
+            .filter(|id| !self.has_rule[*id])
+            .map(|id| format!("\"{}\"", self.color(id)))
+            .collect::<Vec<_>>();
+
+        if !undefined.is_empty() {
+            return Err(anyhow!(
+                "These bags are contained by other bags but have no rule: {}",
+                undefined.join(", ")
+            ));
+        }
+
+        if let Some(cycle) = self.find_cycle() {
+            return Err(self.cycle_error(&cycle));
+        }
+
+        Ok(())
+    }
+
+    fn cycle_error(&self, cycle: &[ColorId]) -> anyhow::Error {
+        anyhow!(
+            "These bags contain each other: {}",
+            cycle
+                .iter()
+                .map(|id| format!("\"{}\"", self.color(*id)))
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        )
+    }
+
+    /// Find a bag that eventually contains itself, returning the path of
+    /// colors from that bag back to itself.
+    pub fn find_cycle(&self) -> Option<Vec<ColorId>> {
+        #[derive(Debug, Eq, PartialEq, Copy, Clone)]
+        enum State {
+            Unvisited,
+            OnPath,
+            Done,
+        }
+
+        let mut state = vec![State::Unvisited; self.len()];
+
+        for start in 0..self.len() {
+            if state[start] != State::Unvisited {
+                continue;
+            }
+
+            // The current path, with the index of the next edge to follow from
+            // each bag on it
+            let mut path = vec![(start, 0)];
+            state[start] = State::OnPath;
+
+            while let Some((id, next_edge)) = path.last_mut() {
+                let id = *id;
+
+                match self.contents[id].get(*next_edge) {
+                    Some(edge) => {
+                        *next_edge += 1;
+
+                        match state[edge.color] {
+                            State::Unvisited => {
+                                state[edge.color] = State::OnPath;
+                                path.push((edge.color, 0));
+                            }
+                            State::OnPath => {
+                                let mut cycle = path
+                                    .iter()
+                                    .map(|(id, _)| *id)
+                                    .skip_while(|id| *id != edge.color)
+                                    .collect::<Vec<_>>();
+                                cycle.push(edge.color);
+
+                                return Some(cycle);
+                            }
+                            State::Done => {}
+                        }
+                    }
+                    None => {
+                        state[id] = State::Done;
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn intern(&mut self, color: &str) -> ColorId {
+        if let Some(id) = self.ids.get(color) {
+            return *id;
+        }
+
+        let id = self.colors.len();
+        self.colors.push(color.to_string());
+        self.ids.insert(color.to_string(), id);
+        self.contents.push(Vec::new());
+        self.containers.push(Vec::new());
+        self.has_rule.push(false);
+
+        id
+    }
+
+    /// The number of colors in the rules.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn id(&self, color: &str) -> Option<ColorId> {
+        self.ids.get(color).copied()
+    }
+
+    fn expect_id(&self, color: &str) -> Result<ColorId> {
+        self.id(color)
+            .ok_or_else(|| anyhow!("Could not find bag \"{}\" in the rules", color))
+    }
+
+    pub fn color(&self, id: ColorId) -> &str {
+        &self.colors[id]
+    }
+
+    /// Every color, in the order they first appear in the rules.
+    pub fn colors(&self) -> impl Iterator<Item = &str> {
+        self.colors.iter().map(String::as_str)
+    }
+
+    /// The bags a bag of this color must directly contain.
+    pub fn contents(&self, id: ColorId) -> &[Edge] {
+        &self.contents[id]
+    }
+
+    /// The bags that must directly contain a bag of this color.
+    pub fn containers(&self, id: ColorId) -> &[Edge] {
+        &self.containers[id]
+    }
+
+    /// The rule for each color that has one, in the order they were added.
+    pub fn rules(&self) -> impl Iterator<Item = Rule> + '_ {
+        self.rule_order.iter().map(move |&id| Rule {
+            color: self.color(id).to_string(),
+            contents: self.contents[id]
+                .iter()
+                .map(|edge| (edge.count, self.color(edge.color).to_string()))
+                .collect(),
+        })
+    }
+
+    /// Every color of bag that eventually contains a bag of `color`.
+    pub fn ancestors(&self, color: &str) -> Result<BTreeSet<&str>> {
+        Ok(self.reachable(self.expect_id(color)?, &self.containers))
+    }
+
+    /// Every color of bag that a bag of `color` eventually contains.
+    pub fn descendants(&self, color: &str) -> Result<BTreeSet<&str>> {
+        Ok(self.reachable(self.expect_id(color)?, &self.contents))
+    }
+
+    /// Colors of bag that contain no other bags, in the order they first
+    /// appear in the rules.
+    pub fn leaves(&self) -> Vec<&str> {
+        (0..self.len())
+            .filter(|id| self.contents[*id].is_empty())
+            .map(|id| self.color(id))
+            .collect()
+    }
+
+    /// Colors of bag that aren't contained by any other bag, in the order
+    /// they first appear in the rules.
+    pub fn roots(&self) -> Vec<&str> {
+        (0..self.len())
+            .filter(|id| self.containers[*id].is_empty())
+            .map(|id| self.color(id))
+            .collect()
+    }
+
+    /// One of the shortest chains of bags from a bag of `from` down to a bag
+    /// of `to`, or `None` if `from` doesn't eventually contain `to`.
+    pub fn path(&self, from: &str, to: &str) -> Result<Option<BagPath>> {
+        let from = self.expect_id(from)?;
+        let to = self.expect_id(to)?;
+
+        // The bag each color was first reached from, and the edge taken
+        let mut reached_from = vec![None; self.len()];
+        let mut queue = VecDeque::from(vec![from]);
+
+        while let Some(id) = queue.pop_front() {
+            if id == to {
+                break;
+            }
+
+            for edge in &self.contents[id] {
+                if reached_from[edge.color].is_none() {
+                    reached_from[edge.color] = Some((id, *edge));
+                    queue.push_back(edge.color);
+                }
+            }
+        }
+
+        if reached_from[to].is_none() {
+            return Ok(None);
+        }
+
+        let mut edges = Vec::new();
+        let mut id = to;
+
+        while let Some((container, edge)) = reached_from[id] {
+            edges.push(edge);
+            id = container;
+        }
+
+        edges.reverse();
+
+        Ok(Some(BagPath { start: from, edges }))
+    }
+
+    /// The total number of bags inside a bag of `color`.
+    pub fn total_contained(&self, color: &str) -> Result<usize> {
+        let id = self.expect_id(color)?;
+
+        self.contained_totals()?[id]
+            .ok_or_else(|| anyhow!("The number of bags inside \"{}\" is too large", color))
+    }
+
+    /// Every color, ordered so that each bag comes after all the bags it
+    /// contains. It's an error if the rules have a cycle.
+    pub fn topological_order(&self) -> Result<Vec<ColorId>> {
+        // The number of edges from each bag that don't yet lead to a bag in the
+        // order. A color listed twice in one rule has two edges, and is taken
+        // off twice, since `containers` has both edges too.
+        let mut remaining = self
+            .contents
+            .iter()
+            .map(|edges| edges.len())
+            .collect::<Vec<_>>();
+        let mut order = (0..self.len())
+            .filter(|id| remaining[*id] == 0)
+            .collect::<Vec<_>>();
+        let mut next = 0;
+
+        while let Some(id) = order.get(next).copied() {
+            next += 1;
+
+            for edge in &self.containers[id] {
+                remaining[edge.color] -= 1;
+
+                if remaining[edge.color] == 0 {
+                    order.push(edge.color);
+                }
+            }
+        }
+
+        if order.len() < self.len() {
+            return Err(match self.find_cycle() {
+                Some(cycle) => self.cycle_error(&cycle),
+                None => anyhow!("The rules have a cycle"),
+            });
+        }
+
+        Ok(order)
+    }
+
+    /// The total number of bags inside a bag of each color, indexed by id, or
+    /// `None` where the total doesn't fit in a `usize`.
+    ///
+    /// Each color is counted once, in topological order, from the totals of
+    /// the bags it directly contains.
+    pub fn contained_totals(&self) -> Result<Vec<Option<usize>>> {
+        let mut totals = vec![Some(0usize); self.len()];
+
+        for id in self.topological_order()? {
+            totals[id] = self.contents[id].iter().try_fold(0usize, |total, edge| {
+                totals[edge.color]?
+                    .checked_add(1)?
+                    .checked_mul(edge.count)?
+                    .checked_add(total)
+            });
+        }
+
+        Ok(totals)
+    }
+
+    /// The colors that eventually contain a bag of each color, indexed by id.
+    ///
+    /// Each color's set is built once, in reverse topological order, from the
+    /// sets of the bags that directly contain it.
+    pub fn ancestor_sets(&self) -> Result<Vec<BTreeSet<ColorId>>> {
+        let mut sets = vec![BTreeSet::new(); self.len()];
+
+        for id in self.topological_order()?.into_iter().rev() {
+            let mut set = BTreeSet::new();
+
+            for edge in &self.containers[id] {
+                set.insert(edge.color);
+                set.extend(&sets[edge.color]);
+            }
+
+            sets[id] = set;
+        }
+
+        Ok(sets)
+    }
+
+    /// Every color reachable from `start` along `edges`, not counting `start`
+    /// itself.
+    fn reachable(&self, start: ColorId, edges: &[Vec<Edge>]) -> BTreeSet<&str> {
+        let mut visited = vec![false; self.len()];
+        let mut stack = vec![start];
+        let mut result = BTreeSet::new();
+
+        while let Some(id) = stack.pop() {
+            for edge in &edges[id] {
+                if !visited[edge.color] {
+                    visited[edge.color] = true;
+                    result.insert(self.color(edge.color));
+                    stack.push(edge.color);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Display for BagRules {
+    /// Write every rule in canonical form, one per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in self.rules() {
+            writeln!(f, "{}", rule)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+        dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+        bright white bags contain 1 shiny gold bag.
+        muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+        shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+        dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+        vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+        faded blue bags contain no other bags.
+        dotted black bags contain no other bags.";
+
+    #[test]
+    fn ancestor_sets_match_ancestors() -> Result<()> {
+        let rules = BagRules::parse(EXAMPLE)?;
+        let sets = rules.ancestor_sets()?;
+
+        for (id, set) in sets.iter().enumerate() {
+            let colors = set.iter().map(|a| rules.color(*a)).collect::<BTreeSet<_>>();
+            assert_eq!(colors, rules.ancestors(rules.color(id))?);
+        }
+
+        assert_eq!(sets[rules.expect_id("shiny gold")?].len(), 4);
+        assert_eq!(rules.total_contained("shiny gold")?, 32);
+
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_inner_color() -> Result<()> {
+        let rules = BagRules::parse(
+            "shiny gold bags contain 1 dark red bag, 2 dark red bags, 1 faded blue bag.
+             dark red bags contain 3 faded blue bags.
+             faded blue bags contain no other bags.",
+        )?;
+
+        let order = rules
+            .topological_order()?
+            .into_iter()
+            .map(|id| rules.color(id))
+            .collect::<Vec<_>>();
+
+        assert_eq!(order, ["faded blue", "dark red", "shiny gold"]);
+        assert_eq!(rules.total_contained("dark red")?, 3);
+        assert_eq!(rules.total_contained("shiny gold")?, 1 + 3 * 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn contained_totals_overflow() -> Result<()> {
+        let mut rules = BagRules::default();
+        rules.add_rule("shiny gold", &[(2, "dark red")])?;
+        rules.add_rule("dark red", &[(usize::MAX, "faded blue")])?;
+        rules.add_rule::<&str>("faded blue", &[])?;
+        rules.validate()?;
+
+        let totals = rules.contained_totals()?;
+
+        assert_eq!(totals[rules.expect_id("faded blue")?], Some(0));
+        assert_eq!(totals[rules.expect_id("dark red")?], Some(usize::MAX));
+        assert_eq!(totals[rules.expect_id("shiny gold")?], None);
+
+        assert_eq!(rules.total_contained("dark red")?, usize::MAX);
+        assert_eq!(
+            rules.total_contained("shiny gold").unwrap_err().to_string(),
+            "The number of bags inside \"shiny gold\" is too large"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Writing the rules graph out as Graphviz DOT or Mermaid, for drawing.
+
+use super::{BagRules, ColorId};
+use anyhow::Result;
+use std::io::Write;
+
+/// Which part of the rules graph to export.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Subgraph {
+    All,
+    /// A color and every bag that eventually contains it
+    Ancestors(String),
+    /// A color and every bag it eventually contains
+    Descendants(String),
+}
+
+impl Subgraph {
+    /// Whether each color, indexed by id, is in the subgraph.
+    fn select(&self, rules: &BagRules) -> Result<Vec<bool>> {
+        let (color, related) = match self {
+            Self::All => return Ok(vec![true; rules.len()]),
+            Self::Ancestors(color) => (color, rules.ancestors(color)?),
+            Self::Descendants(color) => (color, rules.descendants(color)?),
+        };
+
+        let mut selected = vec![false; rules.len()];
+
+        for color in related.into_iter().chain(Some(color.as_str())) {
+            if let Some(id) = rules.id(color) {
+                selected[id] = true;
+            }
+        }
+
+        Ok(selected)
+    }
+}
+
+/// Every edge between two selected colors, as (container, contained, count).
+fn edges<'a>(
+    rules: &'a BagRules,
+    selected: &'a [bool],
+) -> impl Iterator<Item = (ColorId, ColorId, usize)> + 'a {
+    (0..rules.len())
+        .filter(move |id| selected[*id])
+        .flat_map(move |id| {
+            rules
+                .contents(id)
+                .iter()
+                .filter(move |edge| selected[edge.color])
+                .map(move |edge| (id, edge.color, edge.count))
+        })
+}
+
+/// Write the rules as a Graphviz `digraph`, with an edge from each bag to the
+/// bags it contains, labelled with how many.
+pub fn write_dot<W: Write>(rules: &BagRules, subgraph: &Subgraph, mut writer: W) -> Result<()> {
+    let selected = subgraph.select(rules)?;
+    let quote = |id| {
+        format!(
+            "\"{}\"",
+            rules.color(id).replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    };
+
+    writeln!(writer, "digraph bags {{")?;
+
+    for id in (0..rules.len()).filter(|id| selected[*id]) {
+        writeln!(writer, "    {};", quote(id))?;
+    }
+
+    for (from, to, count) in edges(rules, &selected) {
+        writeln!(
+            writer,
+            "    {} -> {} [label=\"{}\"];",
+            quote(from),
+            quote(to),
+            count
+        )?;
+    }
+
+    writeln!(writer, "}}")?;
+
+    Ok(())
+}
+
+/// Write the rules as a Mermaid flowchart, with an edge from each bag to the
+/// bags it contains, labelled with how many.
+pub fn write_mermaid<W: Write>(rules: &BagRules, subgraph: &Subgraph, mut writer: W) -> Result<()> {
+    let selected = subgraph.select(rules)?;
+
+    writeln!(writer, "flowchart LR")?;
+
+    for id in (0..rules.len()).filter(|id| selected[*id]) {
+        writeln!(
+            writer,
+            "    c{}[\"{}\"]",
+            id,
+            rules.color(id).replace('"', "#quot;")
+        )?;
+    }
+
+    for (from, to, count) in edges(rules, &selected) {
+        writeln!(writer, "    c{} -->|{}| c{}", from, count, to)?;
+    }
+
+    Ok(())
+}
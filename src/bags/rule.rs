@@ -0,0 +1,277 @@
+//! A parser and pretty-printer for single rule sentences.
+//!
+//! The grammar is:
+//!
+//! ```text
+//! rule     = color " bags contain " contents "."
+//! contents = "no other bags" | item { ", " item }
+//! item     = count " " color " " ( "bag" | "bags" )
+//! color    = word { " " word }
+//! ```
+//!
+//! where a `word` is one or more lowercase ASCII letters, a `count` is a
+//! positive decimal number, and an item says `bag` if its count is 1 and
+//! `bags` otherwise. A color's words run up to the first `bag` or `bags`, so
+//! neither can be part of a color.
+
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// One rule, saying which bags a bag of some color must directly contain.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Rule {
+    pub color: String,
+    /// How many of each color of bag, in the order written
+    pub contents: Vec<(usize, String)>,
+}
+
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    /// Parse a rule sentence, reporting the column where it stops following
+    /// the grammar.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut cursor = Cursor { input: s, pos: 0 };
+
+        let color = cursor.color()?;
+        cursor.expect(" ")?;
+        cursor.keyword("bags")?;
+        cursor.expect(" ")?;
+        cursor.keyword("contain")?;
+        cursor.expect(" ")?;
+
+        let mut contents = Vec::new();
+
+        if !cursor.eat("no other bags") {
+            loop {
+                let count_pos = cursor.pos;
+                let count = cursor.count()?;
+                cursor.expect(" ")?;
+                let inner = cursor.color()?;
+                cursor.expect(" ")?;
+
+                let noun_pos = cursor.pos;
+                let noun = cursor.word()?;
+                let expected = if count == 1 { "bag" } else { "bags" };
+
+                if noun != expected {
+                    return Err(anyhow!(
+                        "Expected `{}` at column {} to go with the count of {} at column {}, \
+                         found `{}`",
+                        expected,
+                        cursor.column_at(noun_pos),
+                        count,
+                        cursor.column_at(count_pos),
+                        noun
+                    ));
+                }
+
+                contents.push((count, inner));
+
+                if !cursor.eat(", ") {
+                    break;
+                }
+            }
+        }
+
+        cursor.expect(".")?;
+
+        if cursor.pos < s.len() {
+            return Err(cursor.error("the end of the rule"));
+        }
+
+        Ok(Self { color, contents })
+    }
+}
+
+impl Display for Rule {
+    /// Write the rule in canonical form, e.g. `light red bags contain 1 bright
+    /// white bag, 2 muted yellow bags.`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bags contain ", self.color)?;
+
+        if self.contents.is_empty() {
+            return write!(f, "no other bags.");
+        }
+
+        for (i, (count, color)) in self.contents.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(
+                f,
+                "{} {} {}",
+                count,
+                color,
+                if *count == 1 { "bag" } else { "bags" }
+            )?;
+        }
+
+        write!(f, ".")
+    }
+}
+
+/// A position in a rule sentence being parsed.
+struct Cursor<'a> {
+    input: &'a str,
+    /// Byte offset of the next character to read
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// The 1-based column, in characters, of a byte offset.
+    fn column_at(&self, pos: usize) -> usize {
+        self.input[..pos].chars().count() + 1
+    }
+
+    /// An error saying what was expected at the current position, and what
+    /// was found instead.
+    fn error(&self, expected: &str) -> anyhow::Error {
+        let rest = self.rest();
+        let token_len = match rest.find(|c: char| !c.is_ascii_alphanumeric()) {
+            Some(0) => rest.chars().next().map_or(0, char::len_utf8),
+            Some(len) => len,
+            None => rest.len(),
+        };
+
+        let found = if rest.is_empty() {
+            "the end of the line".to_string()
+        } else {
+            format!("`{}`", &rest[..token_len])
+        };
+
+        anyhow!(
+            "Expected {} at column {}, found {}",
+            expected,
+            self.column_at(self.pos),
+            found
+        )
+    }
+
+    /// Skip past `literal` if it comes next.
+    fn eat(&mut self, literal: &str) -> bool {
+        if self.rest().starts_with(literal) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<()> {
+        if self.eat(literal) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{}`", literal)))
+        }
+    }
+
+    /// Read a word that must be exactly `keyword`.
+    fn keyword(&mut self, keyword: &str) -> Result<()> {
+        let start = self.pos;
+
+        match self.word() {
+            Ok(word) if word == keyword => Ok(()),
+            _ => {
+                self.pos = start;
+                Err(self.error(&format!("`{}`", keyword)))
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !c.is_ascii_lowercase())
+            .unwrap_or(rest.len());
+
+        if len == 0 {
+            return Err(self.error("a lowercase word"));
+        }
+
+        self.pos += len;
+
+        Ok(&rest[..len])
+    }
+
+    /// Read the words of a color, stopping before ` bag` or ` bags`.
+    fn color(&mut self) -> Result<String> {
+        let start = self.pos;
+
+        if matches!(self.word(), Ok("bag") | Ok("bags") | Err(_)) {
+            self.pos = start;
+            return Err(self.error("a color"));
+        }
+
+        loop {
+            let end = self.pos;
+
+            if !self.eat(" ") || matches!(self.word(), Ok("bag") | Ok("bags") | Err(_)) {
+                self.pos = end;
+                break;
+            }
+        }
+
+        Ok(self.input[start..self.pos].to_string())
+    }
+
+    fn count(&mut self) -> Result<usize> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        let count = rest[..len]
+            .parse::<usize>()
+            .ok()
+            .filter(|count| *count > 0)
+            .ok_or_else(|| self.error("a count of at least 1"))?;
+
+        self.pos += len;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_of_any_number_of_words() -> Result<()> {
+        let rule = "red bags contain 1 very dark blue bag, 2 bright red bags.".parse::<Rule>()?;
+
+        assert_eq!(rule.color, "red");
+        assert_eq!(
+            rule.contents,
+            [
+                (1, "very dark blue".to_string()),
+                (2, "bright red".to_string())
+            ]
+        );
+        assert_eq!(
+            rule.to_string(),
+            "red bags contain 1 very dark blue bag, 2 bright red bags."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_color() {
+        let err = "red bags contain 2 bags.".parse::<Rule>().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected a color at column 20, found `bags`"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use advent_of_code_2020::passport::{self, table, Passport};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, prelude::*},
+};
+
+const INPUT_FILE: &str = "./input/dec-04-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-04-convert <to-csv|to-json|from-csv|from-json> [input-file]
+
+to-csv, to-json      Convert a passport batch file (default: the day 4 input)
+                     to CSV or JSON.
+from-csv, from-json  Convert CSV or JSON (default: read from stdin) back into
+                     a passport batch file.
+
+Output is written to stdout.";
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let command = args.next().ok_or_else(|| anyhow!(USAGE))?;
+    let input_file = args.next();
+
+    let stdout = io::stdout();
+    let output = stdout.lock();
+
+    match command.as_str() {
+        "to-csv" | "to-json" => {
+            let input_file = input_file.as_deref().unwrap_or(INPUT_FILE);
+            let input = fs::read_to_string(input_file)
+                .with_context(|| format!("Could not open and read file: {}", input_file))?;
+
+            let passports = passport::records(&input)
+                .enumerate()
+                .map(|(i, record)| {
+                    Passport::parse(record)
+                        .with_context(|| format!("Could not parse passport #{}", i + 1))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if command == "to-csv" {
+                table::write_csv(&passports, output)
+            } else {
+                table::write_json(&passports, output)
+            }
+        }
+
+        "from-csv" | "from-json" => {
+            let input: Box<dyn Read> = match &input_file {
+                Some(input_file) => Box::new(
+                    File::open(input_file)
+                        .with_context(|| format!("Could not open file: {}", input_file))?,
+                ),
+                None => Box::new(io::stdin()),
+            };
+
+            let passports = if command == "from-csv" {
+                table::read_csv(input)?
+            } else {
+                table::read_json(input)?
+            };
+
+            passport::write_batch(&passports, output)
+        }
+
+        _ => Err(anyhow!("Unknown command `{}`\n\n{}", command, USAGE)),
+    }
+}
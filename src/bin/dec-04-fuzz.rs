@@ -0,0 +1,191 @@
+use advent_of_code_2020::passport::{self, Passport};
+use anyhow::{anyhow, Context, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{env, fs, panic};
+
+const INPUT_FILE: &str = "./input/dec-04-part-01/input.txt";
+const DEFAULT_ITERATIONS: usize = 100_000;
+
+/// Characters that are likely to trip up a passport tokenizer, including
+/// multibyte characters and the units used by `hgt`.
+const INTERESTING: &[&str] = &[
+    ":", " ", "\n", "\r\n", "\t", "#", "cm", "in", "byr", "hgt", "pid", "0", "9", "é", "€", "🛂",
+];
+
+/// Throws random and mutated batches at the passport tokenizer, checking that
+/// it never panics, that every field it accepts is well-formed, and that every
+/// passport it parses is written back out exactly as it was read.
+///
+/// Usage: `dec-04-fuzz [iterations] [seed]`
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let iterations = args
+        .next()
+        .map(|s| s.parse())
+        .transpose()
+        .context("Could not parse number of iterations")?
+        .unwrap_or(DEFAULT_ITERATIONS);
+    let seed = args
+        .next()
+        .map(|s| s.parse())
+        .transpose()
+        .context("Could not parse seed")?
+        .unwrap_or_else(|| rand::thread_rng().gen());
+
+    let sample = fs::read_to_string(INPUT_FILE)
+        .with_context(|| format!("Could not open and read file: {}", INPUT_FILE))?;
+
+    println!("Fuzzing {} batches with seed {}...", iterations, seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut num_fields = 0;
+    let mut num_errors = 0;
+
+    for i in 0..iterations {
+        let batch = generate_batch(&mut rng, &sample);
+        let outcome = panic::catch_unwind(|| check_batch(&batch));
+
+        match outcome {
+            Ok(Ok((fields, errors))) => {
+                num_fields += fields;
+                num_errors += errors;
+            }
+            Ok(Err(e)) => {
+                return Err(e.context(format!(
+                    "Invariant violated on iteration {}: {:?}",
+                    i, batch
+                )))
+            }
+            Err(_) => {
+                return Err(anyhow!(
+                    "Tokenizer panicked on iteration {} (seed {}): {:?}",
+                    i,
+                    seed,
+                    batch
+                ))
+            }
+        }
+    }
+
+    println!(
+        "No panics! Accepted {} fields and rejected {} malformed tokens.",
+        num_fields, num_errors
+    );
+
+    Ok(())
+}
+
+/// Tokenize a batch, returning the number of accepted fields and rejected tokens.
+fn check_batch(batch: &str) -> Result<(usize, usize)> {
+    let mut num_fields = 0;
+    let mut num_errors = 0;
+
+    for record in passport::records(batch) {
+        if record.is_empty() {
+            return Err(anyhow!("Empty record"));
+        }
+
+        for field in passport::fields(record) {
+            match field {
+                Ok(field) => {
+                    let token = &record[field.offset..];
+
+                    if field.key.is_empty()
+                        || field.value.is_empty()
+                        || field.key.contains(':')
+                        || field.key.contains(char::is_whitespace)
+                        || field.value.contains(char::is_whitespace)
+                        || !token.starts_with(field.key)
+                        || !token[field.key.len() + 1..].starts_with(field.value)
+                    {
+                        return Err(anyhow!("Bad field {:?} in record {:?}", field, record));
+                    }
+
+                    num_fields += 1;
+                }
+                Err(_) => num_errors += 1,
+            }
+        }
+
+        if let Ok(passport) = Passport::parse(record) {
+            if passport.to_string() != record {
+                return Err(anyhow!(
+                    "Passport {:?} was written back out as {:?}",
+                    record,
+                    passport.to_string()
+                ));
+            }
+        }
+    }
+
+    Ok((num_fields, num_errors))
+}
+
+/// Build either a random batch or a mutated slice of `sample`, with even odds.
+fn generate_batch(rng: &mut StdRng, sample: &str) -> String {
+    if rng.gen_bool(0.5) {
+        random_batch(rng)
+    } else {
+        mutate(rng, sample)
+    }
+}
+
+/// Build a batch out of random pieces of `INTERESTING` and random characters.
+fn random_batch(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(0..64);
+    let mut batch = String::new();
+
+    for _ in 0..len {
+        if rng.gen_bool(0.8) {
+            batch.push_str(INTERESTING[rng.gen_range(0..INTERESTING.len())]);
+        } else {
+            batch.push(rng.gen());
+        }
+    }
+
+    batch
+}
+
+/// Take a random slice of a real batch and insert, delete or replace some
+/// characters in it.
+fn mutate(rng: &mut StdRng, sample: &str) -> String {
+    let chars = sample.chars().collect::<Vec<_>>();
+    let start = rng.gen_range(0..chars.len());
+    let end = (start + rng.gen_range(0..256)).min(chars.len());
+    let mut chars = chars[start..end].to_vec();
+
+    for _ in 0..rng.gen_range(0..8) {
+        let pos = rng.gen_range(0..=chars.len());
+        let piece = INTERESTING[rng.gen_range(0..INTERESTING.len())];
+
+        match rng.gen_range(0..3) {
+            0 => chars.splice(pos..pos, piece.chars()).for_each(drop),
+            1 if pos < chars.len() => {
+                chars.remove(pos);
+            }
+            _ if pos < chars.len() => chars[pos] = rng.gen(),
+            _ => {}
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizer_survives_generated_batches() {
+        let sample = fs::read_to_string(INPUT_FILE).unwrap();
+        let mut rng = StdRng::seed_from_u64(2020);
+
+        for i in 0..5_000 {
+            let batch = generate_batch(&mut rng, &sample);
+
+            if let Err(e) = check_batch(&batch) {
+                panic!("Iteration {}: {:#}", i, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use advent_of_code_2020::passport::{self, Level, Passport, Validation};
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs};
+
+const INPUT_FILE: &str = "./input/dec-04-part-01/input.txt";
+
+/// Counts the valid passports in the batch at several levels of strictness,
+/// in a single pass.
+///
+/// Usage: `dec-04-validate [level...]`, where each level is one of `presence`,
+/// `presence-strict`, `values` or `values-strict`. The `-strict` levels reject
+/// North Pole credentials. With no levels given, all of them are reported.
+fn main() -> Result<()> {
+    let validations = env::args()
+        .skip(1)
+        .map(|arg| parse_validation(&arg))
+        .collect::<Result<Vec<_>>>()?;
+    let validations = if validations.is_empty() {
+        Validation::ALL.to_vec()
+    } else {
+        validations
+    };
+
+    let input = fs::read_to_string(INPUT_FILE)
+        .with_context(|| format!("Could not open and read file: {}", INPUT_FILE))?;
+
+    let mut num_passports = 0;
+    let mut num_valid = vec![0; validations.len()];
+
+    for (i, record) in passport::records(&input).enumerate() {
+        let passport = Passport::parse(record)
+            .with_context(|| format!("Could not parse passport #{}", i + 1))?;
+
+        num_passports += 1;
+
+        for (validation, count) in validations.iter().zip(num_valid.iter_mut()) {
+            if passport.validate(*validation) {
+                *count += 1;
+            }
+        }
+    }
+
+    println!("{} passports read.", num_passports);
+    println!(
+        "{:<10} {:<24} {:>5}",
+        "Level", "North Pole credentials", "Valid"
+    );
+
+    for (validation, count) in validations.iter().zip(num_valid) {
+        println!(
+            "{:<10} {:<24} {:>5}",
+            validation.level,
+            if validation.north_pole_credentials {
+                "accepted"
+            } else {
+                "rejected"
+            },
+            count,
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_validation(s: &str) -> Result<Validation> {
+    let (level, north_pole_credentials) = match s.strip_suffix("-strict") {
+        Some(level) => (level, false),
+        None => (s, true),
+    };
+
+    let level = match level {
+        "presence" => Level::Presence,
+        "values" => Level::Values,
+        _ => return Err(anyhow!("Unknown validation level `{}`", s)),
+    };
+
+    Ok(Validation {
+        level,
+        north_pole_credentials,
+    })
+}
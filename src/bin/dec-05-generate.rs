@@ -0,0 +1,166 @@
+use advent_of_code_2020::{
+    args::parse_arg,
+    seat::{generate, Layout, Seat},
+};
+use anyhow::{anyhow, Context, Result};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{env, fs::File, io};
+
+const USAGE: &str = "Usage: dec-05-generate [options] <command>
+
+Commands:
+  seat <row> <col>         Boarding pass for a single seat
+  id <id>                  Boarding pass for a single seat ID
+  manifest <csv-file>      Boarding passes for every booking in a CSV manifest
+                           with a `passenger` column and either an `id` column
+                           or `row` and `col` columns
+  flight                   Boarding passes for a full flight, like the day 5
+                           input
+
+Options:
+  --layout <layout>        Aircraft layout (default: 128x8/FBLR)
+  --shuffle                Shuffle the boarding passes
+  --missing <id|random>    Leave out one seat. `random` picks a seat whose
+                           neighbours are both occupied
+  --empty-front <rows>     Number of empty rows at the front (flight only)
+  --empty-back <rows>      Number of empty rows at the back (flight only)
+  --seed <seed>            Seed for `--shuffle` and `--missing random`
+
+Boarding passes are written to stdout, one per line. For `manifest`, they're
+written as CSV with `passenger` and `pass` columns instead.";
+
+#[derive(Debug, Default)]
+struct Options {
+    layout: Layout,
+    shuffle: bool,
+    missing: Option<Missing>,
+    empty_front: u32,
+    empty_back: u32,
+    seed: Option<u64>,
+}
+
+#[derive(Debug)]
+enum Missing {
+    Id(u64),
+    Random,
+}
+
+fn main() -> Result<()> {
+    let mut options = Options::default();
+    let mut command = Vec::new();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("Missing value for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--layout" => options.layout = parse_arg(&value()?, "layout")?,
+            "--shuffle" => options.shuffle = true,
+            "--missing" => {
+                options.missing = Some(match value()?.as_str() {
+                    "random" => Missing::Random,
+                    id => Missing::Id(parse_arg(id, "seat id")?),
+                })
+            }
+            "--empty-front" => options.empty_front = parse_arg(&value()?, "number of rows")?,
+            "--empty-back" => options.empty_back = parse_arg(&value()?, "number of rows")?,
+            "--seed" => options.seed = Some(parse_arg(&value()?, "seed")?),
+            _ => command.push(arg),
+        }
+    }
+
+    let layout = &options.layout;
+    let command = command.iter().map(String::as_str).collect::<Vec<_>>();
+
+    // Each seat, with its passenger if it came from a manifest
+    let mut seats: Vec<(Option<String>, Seat)> = match command.as_slice() {
+        ["seat", row, col] => {
+            let row = parse_arg(row, "row")?;
+            let col = parse_arg(col, "column")?;
+
+            vec![(None, layout.seat(row, col)?)]
+        }
+
+        ["id", id] => vec![(None, layout.seat_from_id(parse_arg(id, "seat id")?)?)],
+
+        ["manifest", csv_file] => {
+            let file = File::open(csv_file)
+                .with_context(|| format!("Could not open file: {}", csv_file))?;
+
+            generate::read_manifest(layout, file)?
+                .into_iter()
+                .map(|booking| (Some(booking.passenger), booking.seat))
+                .collect()
+        }
+
+        ["flight"] => {
+            let back = layout
+                .rows()
+                .checked_sub(options.empty_back)
+                .ok_or_else(|| anyhow!("Too many empty rows at the back"))?;
+
+            generate::full_rows(layout, options.empty_front..back)?
+                .into_iter()
+                .map(|seat| (None, seat))
+                .collect()
+        }
+
+        _ => return Err(anyhow!(USAGE)),
+    };
+
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    match options.missing {
+        Some(Missing::Id(id)) => {
+            let missing = layout.seat_from_id(id)?;
+            remove_seat(&mut seats, missing)?;
+        }
+
+        Some(Missing::Random) => {
+            let all_seats = seats.iter().map(|(_, seat)| *seat).collect::<Vec<_>>();
+            let missing = *generate::missable_seats(layout, &all_seats)
+                .choose(&mut rng)
+                .ok_or_else(|| anyhow!("No seat has both of its neighbours occupied"))?;
+            remove_seat(&mut seats, missing)?;
+            eprintln!("Left out seat with id {}", layout.id(missing));
+        }
+
+        None => {}
+    }
+
+    if options.shuffle {
+        seats.shuffle(&mut rng);
+    }
+
+    if let ["manifest", _] = command.as_slice() {
+        let mut writer = csv::Writer::from_writer(io::stdout());
+        writer.write_record(["passenger", "pass"])?;
+
+        for (passenger, seat) in seats {
+            writer.write_record([passenger.unwrap_or_default(), layout.encode(seat)])?;
+        }
+
+        writer.flush()?;
+    } else {
+        for (_, seat) in seats {
+            println!("{}", layout.encode(seat));
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_seat(seats: &mut Vec<(Option<String>, Seat)>, missing: Seat) -> Result<()> {
+    let len = seats.len();
+    seats.retain(|(_, seat)| *seat != missing);
+
+    if seats.len() == len {
+        return Err(anyhow!("Seat {:?} is not in the list of seats", missing));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use advent_of_code_2020::seat::{map::Passes, Layout};
+use anyhow::{Context, Result};
+use std::{env, fs::File, io::BufReader, ops::Range};
+
+const INPUT_FILE: &str = "./input/dec-05-part-01/input.txt";
+
+/// Renders the cabin with every seat from the boarding passes marked as
+/// occupied, and reports on the empty seats.
+///
+/// Usage: `dec-05-seat-map [layout]`, where the layout is written like
+/// `128x8/FBLR` (the default).
+fn main() -> Result<()> {
+    let layout = env::args()
+        .nth(1)
+        .map(|s| s.parse::<Layout>())
+        .transpose()
+        .context("Could not parse layout")?
+        .unwrap_or_default();
+
+    let input_file =
+        File::open(INPUT_FILE).with_context(|| format!("Could not open file: {}", INPUT_FILE))?;
+    let reader = BufReader::new(input_file);
+
+    let passes = Passes::read(layout.clone(), reader)?;
+    let seat_map = passes.seat_map;
+
+    println!("{}", seat_map);
+
+    for (line_num, e) in &passes.invalid {
+        println!("Invalid boarding pass on line {}: {}", line_num, e);
+    }
+
+    for (seat, line_nums) in &passes.duplicates {
+        println!(
+            "Seat {} (id {}) appears on lines {:?}",
+            layout.encode(*seat),
+            layout.id(*seat),
+            line_nums
+        );
+    }
+
+    if !passes.invalid.is_empty() || !passes.duplicates.is_empty() {
+        println!();
+    }
+
+    println!(
+        "Missing rows at the front: {}",
+        fmt_rows(seat_map.missing_front_rows())
+    );
+    println!(
+        "Missing rows at the back:  {}",
+        fmt_rows(seat_map.missing_back_rows())
+    );
+
+    println!("\nEmpty seats in the other rows:");
+    for seat in seat_map.empty_seats_in_occupied_rows() {
+        println!(
+            "\t{} (row {}, column {}, id {})",
+            layout.encode(seat),
+            seat.row,
+            seat.col,
+            layout.id(seat)
+        );
+    }
+
+    println!("\nEmpty seats with both neighbours occupied:");
+    for seat in seat_map.seats_between_occupied() {
+        println!("\t{} (id {})", layout.encode(seat), layout.id(seat));
+    }
+
+    Ok(())
+}
+
+fn fmt_rows(rows: Range<u32>) -> String {
+    if rows.is_empty() {
+        "none".to_string()
+    } else {
+        format!("{} row(s), {} to {}", rows.len(), rows.start, rows.end - 1)
+    }
+}
@@ -0,0 +1,146 @@
+use advent_of_code_2020::customs;
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const INPUT_FILE: &str = "./input/dec-06-part-01/input.txt";
+const DEFAULT_ITERATIONS: u32 = 1000;
+
+/// Times the `AnswerSet` bitmask solutions for both parts against the original
+/// `HashSet` and `HashMap` solutions.
+///
+/// Usage: `dec-06-bench [iterations]`. Build with `--release` for meaningful
+/// numbers.
+fn main() -> Result<()> {
+    let iterations = env::args()
+        .nth(1)
+        .map(|s| s.parse())
+        .transpose()
+        .context("Could not parse number of iterations")?
+        .unwrap_or(DEFAULT_ITERATIONS);
+
+    let input = fs::read_to_string(INPUT_FILE)
+        .with_context(|| format!("Could not open and read file: {}", INPUT_FILE))?;
+
+    println!("Running each solution {} times...\n", iterations);
+    println!(
+        "{:<32} {:>8} {:>14} {:>9}",
+        "Solution", "Answer", "Time per run", "Speedup"
+    );
+
+    for (name, baseline, bitmask) in [
+        (
+            "part 1 (union)",
+            part_01_hash_set as fn(&str) -> Result<usize>,
+            part_01_bitmask as fn(&str) -> Result<usize>,
+        ),
+        ("part 2 (intersection)", part_02_hash_map, part_02_bitmask),
+    ] {
+        let (baseline_answer, baseline_time) = time(iterations, || baseline(&input))?;
+        let (bitmask_answer, bitmask_time) = time(iterations, || bitmask(&input))?;
+
+        if baseline_answer != bitmask_answer {
+            return Err(anyhow!(
+                "Answers for {} differ: {} with hashing, {} with bitmasks",
+                name,
+                baseline_answer,
+                bitmask_answer
+            ));
+        }
+
+        println!(
+            "{:<32} {:>8} {:>14?}",
+            format!("{}, hashing", name),
+            baseline_answer,
+            baseline_time
+        );
+        println!(
+            "{:<32} {:>8} {:>14?} {:>8.1}x",
+            format!("{}, bitmask", name),
+            bitmask_answer,
+            bitmask_time,
+            baseline_time.as_secs_f64() / bitmask_time.as_secs_f64()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run a solution `iterations` times, returning its answer and the mean time
+/// per run.
+fn time<F>(iterations: u32, mut solution: F) -> Result<(usize, Duration)>
+where
+    F: FnMut() -> Result<usize>,
+{
+    let mut answer = 0;
+    let start = Instant::now();
+
+    for _ in 0..iterations {
+        answer = black_box(solution()?);
+    }
+
+    Ok((answer, start.elapsed() / iterations.max(1)))
+}
+
+fn part_01_bitmask(input: &str) -> Result<usize> {
+    Ok(customs::parse_groups(input)?
+        .iter()
+        .map(|group| group.union().len())
+        .sum())
+}
+
+fn part_02_bitmask(input: &str) -> Result<usize> {
+    Ok(customs::parse_groups(input)?
+        .iter()
+        .map(|group| group.intersection().len())
+        .sum())
+}
+
+/// The original `HashSet` solution to part 1.
+fn part_01_hash_set(input: &str) -> Result<usize> {
+    let mut answered_questions = HashSet::new();
+    let mut num_answered_questions_per_group = Vec::new();
+
+    for group in input.split("\n\n") {
+        for answered_question in group.trim().replace('\n', "").chars() {
+            answered_questions.insert(answered_question);
+        }
+
+        num_answered_questions_per_group.push(answered_questions.len());
+        answered_questions.clear();
+    }
+
+    Ok(num_answered_questions_per_group.iter().sum())
+}
+
+/// The original `HashMap` solution to part 2.
+fn part_02_hash_map(input: &str) -> Result<usize> {
+    let mut answered_questions = HashMap::<char, usize>::new();
+    let mut num_answered_questions_per_group = Vec::new();
+
+    for group in input.split("\n\n") {
+        let group = group.trim();
+        let group_count = group.split('\n').count();
+
+        for answered_question in group.replace('\n', "").chars() {
+            answered_questions
+                .entry(answered_question)
+                .and_modify(|c| *c += 1)
+                .or_insert(1);
+        }
+
+        let count_questions_all_answered = answered_questions
+            .iter()
+            .filter(|(_ans, ans_count)| **ans_count == group_count)
+            .count();
+
+        num_answered_questions_per_group.push(count_questions_all_answered);
+        answered_questions.clear();
+    }
+
+    Ok(num_answered_questions_per_group.iter().sum())
+}
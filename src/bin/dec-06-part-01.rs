@@ -1,5 +1,5 @@
+use advent_of_code_2020::customs;
 use anyhow::{Context, Result};
-use std::collections::HashSet;
 use std::fs;
 
 const INPUT_FILE: &str = "./input/dec-06-part-01/input.txt";
@@ -8,22 +8,12 @@ fn main() -> Result<()> {
     let input = fs::read_to_string(INPUT_FILE)
         .with_context(|| format!("Could not open and read file: {}", INPUT_FILE))?;
 
-    let mut answered_questions = HashSet::new();
-    let mut num_answered_questions_per_group = Vec::new();
+    let answer = customs::parse_groups(&input)?
+        .iter()
+        .map(|group| group.union().len())
+        .sum::<usize>();
 
-    for group in input.split("\n\n") {
-        for answered_question in group.trim().replace('\n', "").chars() {
-            answered_questions.insert(answered_question);
-        }
-
-        num_answered_questions_per_group.push(answered_questions.len());
-        answered_questions.clear();
-    }
-
-    println!(
-        "Answer: {}",
-        num_answered_questions_per_group.iter().sum::<usize>()
-    );
+    println!("Answer: {}", answer);
 
     Ok(())
 }
@@ -0,0 +1,118 @@
+use advent_of_code_2020::customs::{
+    stats::{GroupStats, Report},
+    Parser, Strictness,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs, io};
+
+const INPUT_FILE: &str = "./input/dec-06-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-06-stats [--alphabet <questions>] [--normalize] \
+                     [--csv questions|groups|sizes]";
+
+/// Prints statistics about every question and group in the declarations file.
+///
+/// Usage: `dec-06-stats [--alphabet <questions>] [--normalize] [--csv
+/// questions|groups|sizes]`. The alphabet is written like `a-zA-Z0-9`, and
+/// defaults to `a-z`. With `--normalize`, characters that aren't questions are
+/// case-folded or dropped, and reported on stderr, instead of being an error.
+/// With `--csv`, only the chosen table is written to stdout, as CSV.
+fn main() -> Result<()> {
+    let mut parser = Parser::default();
+    let mut csv_table = None;
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--alphabet" => {
+                let spec = args.next().ok_or_else(|| anyhow!(USAGE))?;
+                parser.alphabet = spec
+                    .parse()
+                    .with_context(|| format!("Could not parse alphabet `{}`", spec))?;
+            }
+            "--normalize" => parser.strictness = Strictness::Normalize,
+            "--csv" => csv_table = Some(args.next().ok_or_else(|| anyhow!(USAGE))?),
+            _ => return Err(anyhow!(USAGE)),
+        }
+    }
+
+    let input = fs::read_to_string(INPUT_FILE)
+        .with_context(|| format!("Could not open and read file: {}", INPUT_FILE))?;
+    let (groups, irregularities) = parser.parse(&input)?;
+
+    for irregularity in &irregularities {
+        eprintln!("Warning: {}", irregularity);
+    }
+
+    let report = Report::new(&groups, &parser.alphabet);
+
+    if let Some(table) = csv_table {
+        let stdout = io::stdout();
+
+        return match table.as_str() {
+            "questions" => report.write_questions_csv(stdout.lock()),
+            "groups" => report.write_groups_csv(stdout.lock()),
+            "sizes" => report.write_sizes_csv(stdout.lock()),
+            _ => Err(anyhow!("Unknown table `{}`", table)),
+        };
+    }
+
+    println!(
+        "{} people in {} groups.\n",
+        report.num_people,
+        report.groups.len()
+    );
+
+    println!("Question  People  Groups  Unanimous groups");
+    for q in &report.questions {
+        println!(
+            "{:>8}  {:>6}  {:>6}  {:>16}",
+            q.question, q.people, q.groups, q.unanimous_groups
+        );
+    }
+
+    if let Some(largest) = report.largest_groups().first() {
+        println!(
+            "\nLargest groups (size {}): {}",
+            largest.size,
+            fmt_groups(&report.largest_groups())
+        );
+    }
+
+    if let Some(smallest) = report.smallest_groups().first() {
+        println!(
+            "Smallest groups (size {}): {}",
+            smallest.size,
+            fmt_groups(&report.smallest_groups())
+        );
+    }
+
+    let without_agreement = report.groups_without_agreement();
+    println!(
+        "\n{} groups where nobody agreed on anything: {}",
+        without_agreement.len(),
+        fmt_groups(&without_agreement)
+    );
+
+    let max_groups = report.sizes.iter().map(|s| s.groups).max().unwrap_or(0);
+    println!("\nGroup sizes:");
+    for size in &report.sizes {
+        let bar_len = (size.groups * 50 + max_groups - 1) / max_groups.max(1);
+        println!(
+            "{:>4} | {:<50} {}",
+            size.size,
+            "#".repeat(bar_len),
+            size.groups
+        );
+    }
+
+    Ok(())
+}
+
+fn fmt_groups(groups: &[&GroupStats]) -> String {
+    groups
+        .iter()
+        .map(|g| format!("#{}", g.group))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,62 @@
+use advent_of_code_2020::customs::{Combine, Parser, Strictness};
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs};
+
+const INPUT_FILE: &str = "./input/dec-06-part-01/input.txt";
+
+/// Parses the declarations file once, and sums the number of questions
+/// selected in each group by each way of combining answers.
+///
+/// Usage: `dec-06-survey [--alphabet <questions>] [--normalize] [combine...]`,
+/// where each way of combining answers is one of `union`, `intersection`,
+/// `symmetric-difference` or `at-least-<k>`. With none given, the first three
+/// are used. `--alphabet` and `--normalize` work as for `dec-06-stats`.
+fn main() -> Result<()> {
+    let mut parser = Parser::default();
+    let mut combines = Vec::new();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--alphabet" => {
+                let spec = args
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing value for --alphabet"))?;
+                parser.alphabet = spec
+                    .parse()
+                    .with_context(|| format!("Could not parse alphabet `{}`", spec))?;
+            }
+            "--normalize" => parser.strictness = Strictness::Normalize,
+            _ => combines.push(arg.parse::<Combine>()?),
+        }
+    }
+
+    if combines.is_empty() {
+        combines = vec![
+            Combine::Union,
+            Combine::Intersection,
+            Combine::SymmetricDifference,
+        ];
+    }
+
+    let input = fs::read_to_string(INPUT_FILE)
+        .with_context(|| format!("Could not open and read file: {}", INPUT_FILE))?;
+    let (groups, irregularities) = parser.parse(&input)?;
+
+    for irregularity in &irregularities {
+        eprintln!("Warning: {}", irregularity);
+    }
+
+    println!("{} groups read.", groups.len());
+
+    for combine in combines {
+        let answer = groups
+            .iter()
+            .map(|group| group.combine(combine).len())
+            .sum::<usize>();
+
+        println!("{:<20} {:>6}", combine, answer);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use advent_of_code_2020::bags::{
+    export::{self, Subgraph},
+    BagRules,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs, io};
+
+const INPUT_FILE: &str = "./input/dec-07-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-07-export <dot|mermaid> [options] [input-file]
+
+Writes the bag rules (default: the day 7 input) to stdout as a Graphviz DOT or
+Mermaid graph, with an edge from each bag to the bags it contains.
+
+Options:
+  --ancestors <color>    Only the color and the bags that eventually contain it
+  --descendants <color>  Only the color and the bags it eventually contains";
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let format = args.next().ok_or_else(|| anyhow!(USAGE))?;
+    let mut subgraph = Subgraph::All;
+    let mut input_file = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ancestors" => {
+                subgraph = Subgraph::Ancestors(args.next().ok_or_else(|| anyhow!(USAGE))?)
+            }
+            "--descendants" => {
+                subgraph = Subgraph::Descendants(args.next().ok_or_else(|| anyhow!(USAGE))?)
+            }
+            _ if input_file.is_none() => input_file = Some(arg),
+            _ => return Err(anyhow!(USAGE)),
+        }
+    }
+
+    let input_file = input_file.as_deref().unwrap_or(INPUT_FILE);
+    let input = fs::read_to_string(input_file)
+        .with_context(|| format!("Could not open and read file: {}", input_file))?;
+    let rules = BagRules::parse(&input).context("Could not parse file")?;
+
+    let stdout = io::stdout();
+
+    match format.as_str() {
+        "dot" => export::write_dot(&rules, &subgraph, stdout.lock()),
+        "mermaid" => export::write_mermaid(&rules, &subgraph, stdout.lock()),
+        _ => Err(anyhow!(USAGE)),
+    }
+}
@@ -0,0 +1,19 @@
+use advent_of_code_2020::bags::BagRules;
+use anyhow::{Context, Result};
+use std::{env, fs};
+
+const INPUT_FILE: &str = "./input/dec-07-part-01/input.txt";
+
+/// Checks a bag rules file and prints it back out in canonical form.
+///
+/// Usage: `dec-07-format [input-file]`, defaulting to the day 7 input.
+fn main() -> Result<()> {
+    let input_file = env::args().nth(1).unwrap_or_else(|| INPUT_FILE.to_string());
+    let input = fs::read_to_string(&input_file)
+        .with_context(|| format!("Could not open and read file: {}", input_file))?;
+    let rules = BagRules::parse(&input).context("Could not parse file")?;
+
+    print!("{}", rules);
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use advent_of_code_2020::bags::BagRules;
+use anyhow::{anyhow, Context, Result};
+use std::{
+    env, fs,
+    io::{self, prelude::*},
+};
+
+const INPUT_FILE: &str = "./input/dec-07-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-07-query [--rules <file>] [query...]
+
+Answers questions about the bag rules (default: the day 7 input). Each argument
+is one query; with none, queries are read from stdin, one per line.
+
+Queries:
+  containers <color>        Which bags can eventually contain a bag of <color>
+  inside <color>            How many bags are inside a bag of <color>
+  path <color> to <color>   A shortest chain of bags from the first color down
+                            to the second, and how many of the second it holds
+  leaves                    Which bags contain no other bags
+  roots                     Which bags aren't contained by any other bag";
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1).peekable();
+    let mut rules_file = INPUT_FILE.to_string();
+
+    if args.peek().map(String::as_str) == Some("--rules") {
+        args.next();
+        rules_file = args.next().ok_or_else(|| anyhow!(USAGE))?;
+    }
+
+    let input = fs::read_to_string(&rules_file)
+        .with_context(|| format!("Could not open and read file: {}", rules_file))?;
+    let rules = BagRules::parse(&input).context("Could not parse file")?;
+
+    let queries = args.collect::<Vec<_>>();
+
+    if !queries.is_empty() {
+        for query in &queries {
+            answer(&rules, query)?;
+        }
+
+        return Ok(());
+    }
+
+    for line in io::stdin().lock().lines() {
+        let line = line.context("Could not read query from stdin")?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // Keep going after a bad query, so one typo doesn't end the session
+        if let Err(e) = answer(&rules, &line) {
+            eprintln!("Error: {:#}", e);
+        }
+    }
+
+    Ok(())
+}
+
+fn answer(rules: &BagRules, query: &str) -> Result<()> {
+    let query = query.trim();
+    let (command, rest) = match query.find(' ') {
+        Some(i) => (&query[..i], query[i + 1..].trim()),
+        None => (query, ""),
+    };
+
+    match (command, rest) {
+        ("containers", color) if !color.is_empty() => {
+            let ancestors = rules.ancestors(color)?;
+
+            println!(
+                "{} bag(s) can eventually contain a \"{}\" bag: {}",
+                ancestors.len(),
+                color,
+                ancestors.into_iter().collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        ("inside", color) if !color.is_empty() => {
+            println!(
+                "A \"{}\" bag contains {} other bag(s)",
+                color,
+                rules.total_contained(color)?
+            );
+        }
+
+        ("path", colors) => {
+            let (from, to) = colors
+                .split_once(" to ")
+                .ok_or_else(|| anyhow!("Expected `path <color> to <color>`"))?;
+            let (from, to) = (from.trim(), to.trim());
+
+            match rules.path(from, to)? {
+                Some(path) => {
+                    let multiplicity = path
+                        .multiplicity()
+                        .ok_or_else(|| anyhow!("The number of bags along the path is too large"))?;
+
+                    println!(
+                        "{} (so {} \"{}\" bag(s) along this path)",
+                        path.colors()
+                            .map(|id| format!("\"{}\"", rules.color(id)))
+                            .collect::<Vec<_>>()
+                            .join(" -> "),
+                        multiplicity,
+                        to
+                    );
+                }
+                None => println!("A \"{}\" bag never contains a \"{}\" bag", from, to),
+            }
+        }
+
+        ("leaves", "") => println!("{}", rules.leaves().join(", ")),
+
+        ("roots", "") => println!("{}", rules.roots().join(", ")),
+
+        _ => {
+            return Err(anyhow!(
+                "Could not understand query `{}`\n\n{}",
+                query,
+                USAGE
+            ))
+        }
+    }
+
+    Ok(())
+}
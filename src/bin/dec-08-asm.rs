@@ -0,0 +1,65 @@
+use advent_of_code_2020::console::{self, asm};
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs};
+
+const INPUT_FILE: &str = "./input/dec-08-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-08-asm <assemble|disassemble|roundtrip> [input-file]
+
+assemble     Assemble source with labels and comments into a plain program,
+             like the day 8 input.
+disassemble  Disassemble a plain program (default: the day 8 input) into
+             source with addresses and labels.
+roundtrip    Check that disassembling a plain program (default: the day 8
+             input) and assembling it again gives back the same program, and
+             that the program's own text is reproduced exactly.
+
+Output is written to stdout.";
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let command = args.next().ok_or_else(|| anyhow!(USAGE))?;
+    let input_file = args.next().unwrap_or_else(|| INPUT_FILE.to_string());
+    let input = fs::read_to_string(&input_file)
+        .with_context(|| format!("Could not open and read file: {}", input_file))?;
+
+    match command.as_str() {
+        "assemble" => {
+            for instruction in asm::assemble(&input)? {
+                println!("{}", instruction);
+            }
+        }
+
+        "disassemble" => print!("{}", asm::disassemble(&console::parse_program(&input)?)),
+
+        "roundtrip" => {
+            let program = console::parse_program(&input)?;
+            let source = asm::disassemble(&program);
+            let reassembled = asm::assemble(&source).context("Could not assemble disassembly")?;
+
+            if let Some(addr) = (0..program.len().max(reassembled.len()))
+                .find(|addr| program.get(*addr) != reassembled.get(*addr))
+            {
+                return Err(anyhow!(
+                    "Disassembling and assembling changed the program at address {}",
+                    addr
+                ));
+            }
+
+            let text = program
+                .iter()
+                .map(|instruction| format!("{}\n", instruction))
+                .collect::<String>();
+
+            if text.trim_end() != input.trim_end() {
+                return Err(anyhow!("Writing the program out changed its text"));
+            }
+
+            println!("{} instructions round-tripped.", program.len());
+        }
+
+        _ => return Err(anyhow!(USAGE)),
+    }
+
+    Ok(())
+}
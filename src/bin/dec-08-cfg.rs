@@ -0,0 +1,120 @@
+use advent_of_code_2020::console::{
+    asm,
+    cfg::{Cfg, Termination},
+};
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs, io};
+
+const INPUT_FILE: &str = "./input/dec-08-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-08-cfg [--dot] [program-file]";
+
+/// Analyses a boot program (default: the day 8 input) without running it:
+/// its basic blocks, jump targets, unreachable instructions, out-of-bounds
+/// jumps and infinite loops, and whether it terminates.
+///
+/// Usage: `dec-08-cfg [--dot] [program-file]`. With `--dot`, the control-flow
+/// graph is written to stdout as Graphviz DOT instead.
+fn main() -> Result<()> {
+    let mut dot = false;
+    let mut input_file = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--dot" => dot = true,
+            _ if input_file.is_none() => input_file = Some(arg),
+            _ => return Err(anyhow!(USAGE)),
+        }
+    }
+
+    let input_file = input_file.unwrap_or_else(|| INPUT_FILE.to_string());
+    let input = fs::read_to_string(&input_file)
+        .with_context(|| format!("Could not open and read file: {}", input_file))?;
+    let program = asm::assemble(&input).context("Could not parse program")?;
+    let cfg = Cfg::new(&program)?;
+
+    if dot {
+        return cfg.write_dot(io::stdout().lock());
+    }
+
+    let blocks = cfg.blocks();
+    let fmt_blocks = |ids: &[usize]| {
+        ids.iter()
+            .map(|i| format!("{}-{}", blocks[*i].start, blocks[*i].end - 1))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    };
+
+    println!(
+        "{} instructions in {} basic blocks, with {} jump targets.",
+        program.len(),
+        blocks.len(),
+        cfg.jump_targets().len()
+    );
+
+    let unreachable = cfg.unreachable();
+    println!(
+        "{} instructions can never run: {}",
+        unreachable.len(),
+        fmt_ranges(&unreachable)
+    );
+
+    for (addr, target) in cfg.out_of_bounds_jumps() {
+        println!(
+            "Instruction `{}` at {} jumps out of bounds to {}",
+            program[addr], addr, target
+        );
+    }
+
+    let loops = cfg.loops();
+    println!("{} infinite loops:", loops.len());
+    for cycle in &loops {
+        println!(
+            "    {} -> back to {}",
+            fmt_blocks(cycle),
+            fmt_blocks(&cycle[..1])
+        );
+    }
+
+    match cfg.termination() {
+        Termination::Terminates { path } => {
+            println!(
+                "The program terminates, running blocks {}",
+                fmt_blocks(&path)
+            )
+        }
+        Termination::Loops { path, cycle } => println!(
+            "The program never terminates. It runs blocks {}, then loops through {} forever",
+            fmt_blocks(&path),
+            fmt_blocks(&cycle)
+        ),
+        Termination::OutOfBounds { path, addr } => println!(
+            "The program never terminates. It runs blocks {}, then jumps out of bounds to {}",
+            fmt_blocks(&path),
+            addr
+        ),
+    }
+
+    Ok(())
+}
+
+/// Write sorted addresses as ranges, e.g. `3-5, 9`.
+fn fmt_ranges(addrs: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for addr in addrs {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *addr => *end = *addr,
+            _ => ranges.push((*addr, *addr)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|(start, end)| match start == end {
+            true => start.to_string(),
+            false => format!("{}-{}", start, end),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
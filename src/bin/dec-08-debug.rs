@@ -0,0 +1,305 @@
+use advent_of_code_2020::{
+    args::parse_arg,
+    console::{asm, Instruction, InstructionType, Vm},
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    io::{self, prelude::*},
+    str::FromStr,
+};
+
+const INPUT_FILE: &str = "./input/dec-08-part-01/input.txt";
+
+/// How many instructions `continue` runs at most, unless told otherwise, so
+/// that it still comes back with loop detection off.
+const DEFAULT_MAX_STEPS: usize = 1_000_000;
+
+const HELP: &str = "Commands:
+  s, step [n]             Run the next n instructions (default: 1)
+  c, continue [max-steps] Run until a breakpoint or watch is hit, the
+                          program halts, or <max-steps> instructions have run
+                          (default: 1000000)
+  b, break <addr>         Stop before running the instruction at <addr>
+  d, delete <addr>        Remove the breakpoint at <addr>
+  breakpoints             List breakpoints
+  w, watch [value]        Stop when the accumulator changes, or when it
+                          becomes <value>
+  unwatch                 Remove the watch
+  p, print                Show the machine state and the next instruction
+  l, list [addr] [n]      Show <n> instructions (default: 10) from <addr>
+                          (default: a little before the next instruction),
+                          with how many times each has run
+  patch <addr> [ins]      Swap the `jmp` or `nop` at <addr>, or replace it with
+                          <ins>, e.g. `acc +3`. Loop detection starts over, so
+                          the program can carry on from a detected loop
+  loops <on|off>          Turn stopping before an instruction runs twice on or
+                          off (default: on)
+  r, reset                Start the program again, keeping patches
+  h, help                 Show this help
+  q, quit                 Exit";
+
+/// When to stop because of the accumulator.
+#[derive(Debug, Copy, Clone)]
+enum Watch {
+    Change,
+    Value(i64),
+}
+
+struct Debugger {
+    vm: Vm,
+    breakpoints: BTreeSet<usize>,
+    watch: Option<Watch>,
+}
+
+/// Runs a boot program (default: the day 8 input) under an interactive
+/// debugger, reading commands from stdin.
+///
+/// Usage: `dec-08-debug [program-file]`. The program can be plain day 8 code,
+/// or assembly source with labels and comments.
+fn main() -> Result<()> {
+    let input_file = env::args().nth(1).unwrap_or_else(|| INPUT_FILE.to_string());
+    let input = fs::read_to_string(&input_file)
+        .with_context(|| format!("Could not open and read file: {}", input_file))?;
+    let program = asm::assemble(&input).context("Could not parse program")?;
+
+    println!(
+        "Loaded {} instructions. Type `help` for commands.",
+        program.len()
+    );
+
+    let mut debugger = Debugger {
+        vm: Vm::new(program),
+        breakpoints: BTreeSet::new(),
+        watch: None,
+    };
+
+    debugger.print();
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("(dbg) ");
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line.context("Could not read command from stdin")?,
+            None => break,
+        };
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        match words.as_slice() {
+            [] => continue,
+            ["q"] | ["quit"] => break,
+            words => {
+                // A bad command shouldn't end the session
+                if let Err(e) = debugger.command(words) {
+                    println!("Error: {:#}", e);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Debugger {
+    fn command(&mut self, words: &[&str]) -> Result<()> {
+        match words {
+            ["s"] | ["step"] => self.step(1)?,
+            ["s", n] | ["step", n] => self.step(parse_arg(n, "number of steps")?)?,
+
+            ["c"] | ["continue"] => self.cont(DEFAULT_MAX_STEPS)?,
+            ["c", n] | ["continue", n] => self.cont(parse_arg(n, "maximum number of steps")?)?,
+
+            ["b", addr] | ["break", addr] => {
+                let addr = self.parse_addr(addr)?;
+                self.breakpoints.insert(addr);
+                println!("Breakpoint at {}: {}", addr, self.vm.program()[addr]);
+            }
+
+            ["d", addr] | ["delete", addr] => {
+                let addr = parse_arg(addr, "address")?;
+
+                if !self.breakpoints.remove(&addr) {
+                    return Err(anyhow!("There's no breakpoint at {}", addr));
+                }
+            }
+
+            ["breakpoints"] => {
+                for addr in &self.breakpoints {
+                    println!("{:>6}  {}", addr, self.vm.program()[*addr]);
+                }
+            }
+
+            ["w"] | ["watch"] => self.watch = Some(Watch::Change),
+            ["w", value] | ["watch", value] => {
+                self.watch = Some(Watch::Value(parse_arg(value, "accumulator value")?))
+            }
+            ["unwatch"] => self.watch = None,
+
+            ["p"] | ["print"] => self.print(),
+
+            ["l"] | ["list"] => {
+                let start = self.vm.addr().unwrap_or(0).saturating_sub(3);
+                self.list(start, 10);
+            }
+            ["l", addr] | ["list", addr] => self.list(self.parse_addr(addr)?, 10),
+            ["l", addr, n] | ["list", addr, n] => self.list(
+                self.parse_addr(addr)?,
+                parse_arg(n, "number of instructions")?,
+            ),
+
+            ["patch", addr, ins @ ..] => {
+                let addr = self.parse_addr(addr)?;
+                let old = self.vm.program()[addr];
+
+                let new = if ins.is_empty() {
+                    if old.ins_type == InstructionType::Acc {
+                        return Err(anyhow!("Can only swap `jmp` and `nop`, not `{}`", old));
+                    }
+
+                    old.swap_nop_and_jmp()
+                } else {
+                    Instruction::from_str(&ins.join(" "))?
+                };
+
+                self.vm.patch(addr, new)?;
+                // Let the patched program carry on past a loop it already
+                // stopped at
+                self.vm.resume();
+                println!("Patched {}: `{}` -> `{}`", addr, old, new);
+            }
+
+            ["loops", "on"] => self.vm.set_loop_detection(true),
+            ["loops", "off"] => self.vm.set_loop_detection(false),
+
+            ["r"] | ["reset"] => {
+                self.vm.reset();
+                self.print();
+            }
+
+            ["h"] | ["help"] => println!("{}", HELP),
+
+            _ => {
+                return Err(anyhow!(
+                    "Unknown command `{}`. Type `help` for commands.",
+                    words.join(" ")
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn step(&mut self, n: usize) -> Result<()> {
+        for _ in 0..n {
+            if let Some(halt) = self.vm.step()? {
+                println!("Program halted: {}", halt);
+                break;
+            }
+        }
+
+        self.print();
+
+        Ok(())
+    }
+
+    fn cont(&mut self, max_steps: usize) -> Result<()> {
+        let breakpoints = &self.breakpoints;
+        let watch = self.watch;
+        let mut last_acc = self.vm.accumulator();
+        let mut steps = 0;
+        let mut watch_hit = false;
+
+        if max_steps == 0 {
+            return Err(anyhow!("The maximum number of steps must be at least 1"));
+        }
+
+        let halt = self.vm.run_until(|vm| {
+            steps += 1;
+            watch_hit = match watch {
+                Some(Watch::Change) => vm.accumulator() != last_acc,
+                Some(Watch::Value(value)) => vm.accumulator() == value && last_acc != value,
+                None => false,
+            };
+            last_acc = vm.accumulator();
+
+            watch_hit
+                || matches!(vm.addr(), Some(addr) if breakpoints.contains(&addr))
+                || steps == max_steps
+        })?;
+
+        match halt {
+            Some(halt) => println!("Program halted: {}", halt),
+            None => match self.vm.addr() {
+                Some(addr) if self.breakpoints.contains(&addr) => {
+                    println!("Breakpoint at {}", addr)
+                }
+                _ if watch_hit => println!("Watch hit"),
+                _ => println!("Stopped after {} steps", steps),
+            },
+        }
+
+        self.print();
+
+        Ok(())
+    }
+
+    fn print(&self) {
+        let state = self.vm.state();
+
+        print!(
+            "ins_ptr: {}, accumulator: {}",
+            state.ins_ptr, state.accumulator
+        );
+
+        match self.vm.addr() {
+            Some(addr) => println!(
+                ", next: `{}` (run {} time(s))",
+                self.vm.program()[addr],
+                self.vm.run_counts()[addr]
+            ),
+            None => println!(", outside the program"),
+        }
+    }
+
+    fn list(&self, start: usize, n: usize) {
+        let instructions = self.vm.program().iter().zip(self.vm.run_counts());
+
+        for (addr, (instruction, run_count)) in instructions.enumerate().skip(start).take(n) {
+            let current = if self.vm.addr() == Some(addr) {
+                '>'
+            } else {
+                ' '
+            };
+            let breakpoint = if self.breakpoints.contains(&addr) {
+                '*'
+            } else {
+                ' '
+            };
+
+            println!(
+                "{} {}{:>5}  {:<10} run {} time(s)",
+                current,
+                breakpoint,
+                addr,
+                instruction.to_string(),
+                run_count
+            );
+        }
+    }
+
+    fn parse_addr(&self, s: &str) -> Result<usize> {
+        let addr = parse_arg(s, "address")?;
+
+        if addr >= self.vm.program().len() {
+            return Err(anyhow!("There's no instruction at address {}", addr));
+        }
+
+        Ok(addr)
+    }
+}
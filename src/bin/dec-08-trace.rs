@@ -0,0 +1,250 @@
+use advent_of_code_2020::{
+    args::parse_arg,
+    console::{
+        asm,
+        trace::{self, Recorder, Step},
+        Vm,
+    },
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fs::{self, File},
+    io::{self, prelude::*, BufReader, BufWriter},
+};
+
+const INPUT_FILE: &str = "./input/dec-08-part-01/input.txt";
+
+const USAGE: &str = "Usage: dec-08-trace <command>
+
+Commands:
+  record <trace-file> [program-file] [--flip <addr>]
+      Run a program (default: the day 8 input) until it halts, writing every
+      step to <trace-file> as JSON lines. `--flip` swaps the `jmp` or `nop` at
+      <addr> first.
+  show <trace-file> [from] [n]
+      Print <n> steps (default: 20) starting at step <from> (default: 0).
+  diff <trace-a> <trace-b>
+      Print where two traces first run different instructions, and where
+      their accumulators first differ.
+  inspect <trace-file> [other-trace-file]
+      Step backwards and forwards through one trace, or two side by side,
+      reading commands from stdin.";
+
+const INSPECT_HELP: &str = "Commands:
+  n, next [k]     Move forwards <k> steps (default: 1)
+  p, prev [k]     Move backwards <k> steps (default: 1)
+  g, goto <i>     Move to step <i>
+  find <addr>     Move forwards to the next step that runs the instruction at
+                  <addr>
+  fork            Move to where the traces first run different instructions
+  diverge         Move to where the traces' accumulators first differ
+  h, help         Show this help
+  q, quit         Exit";
+
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["record", trace_file, rest @ ..] => record(trace_file, rest),
+
+        ["show", trace_file, rest @ ..] => {
+            let steps = read(trace_file)?;
+            let from = rest.first().map(|s| parse_arg(s, "step")).transpose()?;
+            let n = rest
+                .get(1)
+                .map(|s| parse_arg(s, "number of steps"))
+                .transpose()?;
+
+            for (i, step) in steps
+                .iter()
+                .enumerate()
+                .skip(from.unwrap_or(0))
+                .take(n.unwrap_or(20))
+            {
+                println!("{}", fmt_step(i, step));
+            }
+
+            Ok(())
+        }
+
+        ["diff", a, b] => {
+            let (a, b) = (read(a)?, read(b)?);
+            diff(&a, &b);
+            Ok(())
+        }
+
+        ["inspect", trace_file] => inspect(read(trace_file)?, None),
+        ["inspect", trace_file, other] => inspect(read(trace_file)?, Some(read(other)?)),
+
+        _ => Err(anyhow!(USAGE)),
+    }
+}
+
+fn record(trace_file: &str, args: &[&str]) -> Result<()> {
+    let mut program_file = INPUT_FILE;
+    let mut flip = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--flip" => {
+                let addr = args.next().ok_or_else(|| anyhow!(USAGE))?;
+                flip = Some(parse_arg::<usize>(addr, "address")?);
+            }
+            _ => program_file = arg,
+        }
+    }
+
+    let input = fs::read_to_string(program_file)
+        .with_context(|| format!("Could not open and read file: {}", program_file))?;
+    let mut vm = Vm::new(asm::assemble(&input).context("Could not parse program")?);
+
+    if let Some(addr) = flip {
+        let instruction = vm
+            .program()
+            .get(addr)
+            .ok_or_else(|| anyhow!("There's no instruction at address {}", addr))?
+            .swap_nop_and_jmp();
+        vm.patch(addr, instruction)?;
+    }
+
+    let file = File::create(trace_file)
+        .with_context(|| format!("Could not create file: {}", trace_file))?;
+    let mut recorder = Recorder::new(BufWriter::new(file));
+    let halt = recorder.run(&mut vm)?;
+
+    println!(
+        "Recorded {} steps to {}. Program halted: {}. Accumulator: {}",
+        recorder.steps(),
+        trace_file,
+        halt,
+        vm.accumulator()
+    );
+
+    Ok(())
+}
+
+fn diff(a: &[Step], b: &[Step]) {
+    match trace::path_divergence(a, b) {
+        Some(i) => {
+            println!("The traces first run different instructions at step {}:", i);
+            print_side_by_side(i, a, b);
+        }
+        None => println!("The traces run the same instructions."),
+    }
+
+    match trace::accumulator_divergence(a, b) {
+        Some(i) => {
+            println!("The accumulators first differ after step {}:", i);
+            print_side_by_side(i, a, b);
+        }
+        None => println!(
+            "The accumulators agree for all {} steps both traces have.",
+            a.len().min(b.len())
+        ),
+    }
+}
+
+fn inspect(steps: Vec<Step>, other: Option<Vec<Step>>) -> Result<()> {
+    let len = other
+        .as_ref()
+        .map_or(steps.len(), |other| steps.len().max(other.len()));
+    let other = other.unwrap_or_default();
+    let two_traces = !other.is_empty();
+
+    if len == 0 {
+        return Err(anyhow!("The trace is empty"));
+    }
+
+    println!("{} steps. Type `help` for commands.", len);
+
+    let mut i = 0;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        if two_traces {
+            print_side_by_side(i, &steps, &other);
+        } else {
+            println!("{}", fmt_step(i, &steps[i]));
+        }
+
+        print!("(trace) ");
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line.context("Could not read command from stdin")?,
+            None => break,
+        };
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        let target = match words.as_slice() {
+            [] => continue,
+            ["q"] | ["quit"] => break,
+            ["n"] | ["next"] => Ok(i + 1),
+            ["n", k] | ["next", k] => {
+                parse_arg::<usize>(k, "number of steps").map(|k| i.saturating_add(k))
+            }
+            ["p"] | ["prev"] => Ok(i.saturating_sub(1)),
+            ["p", k] | ["prev", k] => {
+                parse_arg::<usize>(k, "number of steps").map(|k| i.saturating_sub(k))
+            }
+            ["g", step] | ["goto", step] => parse_arg(step, "step"),
+            ["find", addr] => parse_arg::<usize>(addr, "address").and_then(|addr| {
+                steps
+                    .iter()
+                    .enumerate()
+                    .skip(i + 1)
+                    .find(|(_, step)| step.addr == addr)
+                    .map(|(j, _)| j)
+                    .ok_or_else(|| anyhow!("No later step runs the instruction at {}", addr))
+            }),
+            ["fork"] if two_traces => trace::path_divergence(&steps, &other)
+                .ok_or_else(|| anyhow!("The traces run the same instructions")),
+            ["diverge"] if two_traces => trace::accumulator_divergence(&steps, &other)
+                .ok_or_else(|| anyhow!("The accumulators never differ")),
+            ["h"] | ["help"] => {
+                println!("{}", INSPECT_HELP);
+                continue;
+            }
+            _ => Err(anyhow!("Unknown command. Type `help` for commands.")),
+        };
+
+        match target {
+            Ok(target) => i = target.min(len - 1),
+            Err(e) => println!("Error: {:#}", e),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_side_by_side(i: usize, a: &[Step], b: &[Step]) {
+    for (name, steps) in [("a", a), ("b", b)] {
+        match steps.get(i) {
+            Some(step) => println!("  {}: {}", name, fmt_step(i, step)),
+            None => println!("  {}: #{:<6} (trace has ended)", name, i),
+        }
+    }
+}
+
+fn fmt_step(i: usize, step: &Step) -> String {
+    format!(
+        "#{:<6} {:>5}  {:<10} acc {} -> {}",
+        i,
+        step.addr,
+        step.instruction.to_string(),
+        step.acc_before,
+        step.acc_after
+    )
+}
+
+fn read(trace_file: &str) -> Result<Vec<Step>> {
+    let file =
+        File::open(trace_file).with_context(|| format!("Could not open file: {}", trace_file))?;
+
+    trace::read_trace(BufReader::new(file))
+        .with_context(|| format!("Could not read trace: {}", trace_file))
+}
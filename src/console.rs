@@ -0,0 +1,376 @@
+//! The handheld game console's boot code, as used on day 8.
+//!
+//! A program is a list of instructions, one per line, each an operation and a
+//! signed argument:
+//!
+//! ```text
+//! nop +0
+//! acc +1
+//! jmp -1
+//! ```
+
+pub mod asm;
+pub mod cfg;
+pub mod repair;
+pub mod trace;
+
+use anyhow::{anyhow, Context, Result};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum InstructionType {
+    /// Increase or decrease the global accumulator by the argument.
+    /// Note that the accumulator starts at 0.
+    Acc,
+    /// Jump to a new instruction *relative to this `jmp` instruction*.
+    Jmp,
+    /// Do nothing. Go to the next instruction.
+    Nop,
+}
+
+impl FromStr for InstructionType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "acc" => Ok(Self::Acc),
+            "jmp" => Ok(Self::Jmp),
+            "nop" => Ok(Self::Nop),
+            _ => Err(anyhow!("Unknown instruction `{}`", s)),
+        }
+    }
+}
+
+impl Display for InstructionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Acc => f.pad("acc"),
+            Self::Jmp => f.pad("jmp"),
+            Self::Nop => f.pad("nop"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Instruction {
+    /// The instruction's type
+    pub ins_type: InstructionType,
+    /// The instruction's argument
+    pub arg: i64,
+}
+
+impl Instruction {
+    pub fn new(ins_type: InstructionType, arg: i64) -> Self {
+        Self { ins_type, arg }
+    }
+
+    /// The instruction with `jmp` and `nop` swapped. `acc` is left alone.
+    pub fn swap_nop_and_jmp(&self) -> Self {
+        Self {
+            ins_type: match self.ins_type {
+                InstructionType::Jmp => InstructionType::Nop,
+                InstructionType::Nop => InstructionType::Jmp,
+                t => t,
+            },
+            ..*self
+        }
+    }
+
+    /// The address of the instruction that runs after this one, if this one
+    /// is at `addr`. It may be outside the program.
+    pub fn next_addr(&self, addr: i64) -> Result<i64> {
+        let offset = match self.ins_type {
+            InstructionType::Jmp => self.arg,
+            InstructionType::Acc | InstructionType::Nop => 1,
+        };
+
+        addr.checked_add(offset)
+            .ok_or_else(|| anyhow!("Jumping {} from address {} overflows", offset, addr))
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = anyhow::Error;
+
+    /// Parse an instruction like `acc +5` or `jmp -3`. The operation and
+    /// argument can be separated by any amount of whitespace.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            [ins_type, arg] => Ok(Self {
+                ins_type: ins_type.parse()?,
+                arg: arg
+                    .parse()
+                    .with_context(|| format!("Could not parse argument to integer: {}", arg))?,
+            }),
+            _ => Err(anyhow!("Expected an instruction and an argument: {}", s)),
+        }
+    }
+}
+
+impl Display for Instruction {
+    /// Write the instruction the way it's written in a program, e.g. `acc +5`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.ins_type, self.arg)
+    }
+}
+
+/// Parse a program, one instruction per line. Blank lines are ignored.
+///
+/// This is the plain format of the day 8 input. See [`asm::assemble`] for
+/// programs with labels and comments.
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_num, line)| {
+            line.trim()
+                .parse()
+                .with_context(|| format!("Could not parse instruction on line {}", line_num + 1))
+        })
+        .collect()
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub struct Machine {
+    /// The address of the next instruction to run. It can be outside the
+    /// program once the program has halted.
+    pub ins_ptr: i64,
+    pub accumulator: i64,
+}
+
+/// Why a program stopped running.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Halt {
+    /// The instruction pointer moved to just past the last instruction.
+    Terminated,
+    /// The instruction at `addr` was about to run for a second time, so the
+    /// program would loop forever.
+    LoopDetected { addr: usize },
+    /// The instruction pointer moved somewhere other than an instruction or
+    /// just past the last one.
+    OutOfBounds { addr: i64 },
+}
+
+impl Display for Halt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Terminated => write!(f, "terminated"),
+            Self::LoopDetected { addr } => {
+                write!(f, "loop detected: instruction {} would run twice", addr)
+            }
+            Self::OutOfBounds { addr } => write!(f, "jumped out of bounds to {}", addr),
+        }
+    }
+}
+
+/// Runs a program one instruction at a time, keeping count of how many times
+/// each instruction has run.
+#[derive(Debug, Clone)]
+pub struct Vm {
+    program: Vec<Instruction>,
+    state: Machine,
+    run_counts: Vec<usize>,
+    /// Whether each instruction has run since the program started or was
+    /// last resumed, for loop detection
+    visited: Vec<bool>,
+    /// Whether to halt with [`Halt::LoopDetected`] before an instruction runs
+    /// a second time
+    loop_detection: bool,
+}
+
+impl Vm {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            run_counts: vec![0; program.len()],
+            visited: vec![false; program.len()],
+            program,
+            state: Machine::default(),
+            loop_detection: true,
+        }
+    }
+
+    /// Turn halting with [`Halt::LoopDetected`] on or off. It's on by
+    /// default. With it off, a looping program runs until stopped some other
+    /// way.
+    pub fn set_loop_detection(&mut self, enabled: bool) {
+        self.loop_detection = enabled;
+    }
+
+    /// Replace the instruction at `addr`, returning the old one. The machine
+    /// state and run counts are left alone.
+    pub fn patch(&mut self, addr: usize, instruction: Instruction) -> Result<Instruction> {
+        let slot = self
+            .program
+            .get_mut(addr)
+            .ok_or_else(|| anyhow!("There's no instruction at address {}", addr))?;
+
+        Ok(std::mem::replace(slot, instruction))
+    }
+
+    /// Start the program again from the beginning, keeping any patches.
+    pub fn reset(&mut self) {
+        self.state = Machine::default();
+        self.run_counts.iter_mut().for_each(|count| *count = 0);
+        self.resume();
+    }
+
+    /// Forget which instructions have already run as far as loop detection
+    /// goes, so that a program halted with [`Halt::LoopDetected`] can carry
+    /// on, e.g. after patching it. The machine state and run counts are left
+    /// alone, and a loop is still detected the next time an instruction runs
+    /// twice.
+    pub fn resume(&mut self) {
+        self.visited.iter_mut().for_each(|visited| *visited = false);
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    pub fn state(&self) -> Machine {
+        self.state
+    }
+
+    pub fn accumulator(&self) -> i64 {
+        self.state.accumulator
+    }
+
+    /// How many times each instruction has run, indexed by address.
+    pub fn run_counts(&self) -> &[usize] {
+        &self.run_counts
+    }
+
+    /// The address of the next instruction to run, or `None` if the
+    /// instruction pointer is outside the program.
+    pub fn addr(&self) -> Option<usize> {
+        usize::try_from(self.state.ins_ptr)
+            .ok()
+            .filter(|addr| *addr < self.program.len())
+    }
+
+    /// The next instruction to run, if the instruction pointer is in the
+    /// program.
+    pub fn current_instruction(&self) -> Option<&Instruction> {
+        self.addr().map(|addr| &self.program[addr])
+    }
+
+    /// Why the program can't run any further, or `None` if it can.
+    pub fn halt(&self) -> Option<Halt> {
+        match self.addr() {
+            Some(addr) if self.loop_detection && self.visited[addr] => {
+                Some(Halt::LoopDetected { addr })
+            }
+            Some(_) => None,
+            None if self.state.ins_ptr == self.program.len() as i64 => Some(Halt::Terminated),
+            None => Some(Halt::OutOfBounds {
+                addr: self.state.ins_ptr,
+            }),
+        }
+    }
+
+    /// Run the next instruction, unless the program has halted, in which case
+    /// return why.
+    pub fn step(&mut self) -> Result<Option<Halt>> {
+        if let Some(halt) = self.halt() {
+            return Ok(Some(halt));
+        }
+
+        let addr = self.state.ins_ptr as usize;
+        let instruction = self.program[addr];
+
+        if instruction.ins_type == InstructionType::Acc {
+            self.state.accumulator = self
+                .state
+                .accumulator
+                .checked_add(instruction.arg)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Accumulator overflowed running `{}` at address {}",
+                        instruction,
+                        addr
+                    )
+                })?;
+        }
+
+        self.state.ins_ptr = instruction.next_addr(self.state.ins_ptr)?;
+        self.run_counts[addr] += 1;
+        self.visited[addr] = true;
+
+        Ok(None)
+    }
+
+    /// Run instructions until the program halts, or `predicate` returns
+    /// `true` after an instruction runs. Returns why the program halted, or
+    /// `None` if it was stopped by `predicate`.
+    pub fn run_until<F>(&mut self, mut predicate: F) -> Result<Option<Halt>>
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        loop {
+            if let Some(halt) = self.step()? {
+                return Ok(Some(halt));
+            }
+
+            if predicate(self) {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Run instructions until the program halts, and return why.
+    pub fn run(&mut self) -> Result<Halt> {
+        loop {
+            if let Some(halt) = self.step()? {
+                return Ok(halt);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn patch_after_loop_and_resume() -> Result<()> {
+        let mut vm = Vm::new(parse_program(EXAMPLE)?);
+
+        assert_eq!(vm.run()?, Halt::LoopDetected { addr: 1 });
+        assert_eq!(vm.accumulator(), 5);
+
+        vm.patch(7, "nop -4".parse()?)?;
+
+        // Patching alone doesn't get past the loop that was already detected
+        assert_eq!(vm.step()?, Some(Halt::LoopDetected { addr: 1 }));
+
+        vm.resume();
+
+        // 1, 2, 6, 7 and 8 run once more
+        assert_eq!(vm.run()?, Halt::Terminated);
+        assert_eq!(vm.accumulator(), 13);
+        assert_eq!(vm.run_counts(), [1, 2, 2, 1, 1, 0, 2, 2, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resume_still_detects_loops() -> Result<()> {
+        let mut vm = Vm::new(parse_program(EXAMPLE)?);
+
+        assert_eq!(vm.run()?, Halt::LoopDetected { addr: 1 });
+
+        vm.resume();
+
+        assert_eq!(vm.run()?, Halt::LoopDetected { addr: 1 });
+        assert_eq!(vm.accumulator(), 10);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+//! An assembler and disassembler for boot code with labels and comments.
+//!
+//! On top of the plain day 8 format, assembly source can have:
+//!
+//! - comments, from `;` or `#` to the end of the line;
+//! - blank lines, and any amount of whitespace around each part of a line;
+//! - labels, written `name:` before an instruction or on a line of their own,
+//!   which mark the address of the next instruction;
+//! - label names in place of the argument to `jmp` and `nop`, which are
+//!   resolved to the offset from the instruction to the label.
+//!
+//! ```text
+//! start:  nop +0
+//! loop:   acc +1      ; count up
+//!         jmp loop
+//! ```
+
+use super::{Instruction, InstructionType};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+};
+
+/// An instruction's argument, before labels are resolved.
+enum Arg<'a> {
+    Offset(i64),
+    Label(&'a str),
+}
+
+/// Assemble source into a program.
+pub fn assemble(source: &str) -> Result<Vec<Instruction>> {
+    // The address of each label, and the line it was defined on
+    let mut labels = HashMap::new();
+    let mut instructions = Vec::new();
+
+    for (line_num, line) in source.lines().enumerate() {
+        let line_num = line_num + 1;
+        let mut rest = line.split([';', '#']).next().unwrap_or("").trim();
+
+        while let Some((label, after)) = split_label(rest) {
+            if let Some((_, first_line)) = labels.insert(label, (instructions.len(), line_num)) {
+                return Err(anyhow!(
+                    "Label `{}` on line {} was already defined on line {}",
+                    label,
+                    line_num,
+                    first_line
+                ));
+            }
+
+            rest = after.trim_start();
+        }
+
+        if rest.is_empty() {
+            continue;
+        }
+
+        let instruction = parse_instruction(rest)
+            .with_context(|| format!("Could not assemble line {}: {}", line_num, line.trim()))?;
+        instructions.push((line_num, instruction));
+    }
+
+    instructions
+        .into_iter()
+        .enumerate()
+        .map(|(addr, (line_num, (ins_type, arg)))| {
+            let arg = match arg {
+                Arg::Offset(offset) => offset,
+                Arg::Label(label) => match labels.get(label) {
+                    Some((target, _)) => *target as i64 - addr as i64,
+                    None => {
+                        return Err(anyhow!("Undefined label `{}` on line {}", label, line_num))
+                    }
+                },
+            };
+
+            Ok(Instruction::new(ins_type, arg))
+        })
+        .collect()
+}
+
+/// Split `name:` off the start of a line, if it's there.
+fn split_label(s: &str) -> Option<(&str, &str)> {
+    let (label, rest) = s.split_once(':')?;
+
+    if is_label(label) {
+        Some((label, rest))
+    } else {
+        None
+    }
+}
+
+fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_instruction(s: &str) -> Result<(InstructionType, Arg<'_>)> {
+    let (ins_type, arg) = match s.split_whitespace().collect::<Vec<_>>()[..] {
+        [ins_type, arg] => (ins_type.parse::<InstructionType>()?, arg),
+        _ => return Err(anyhow!("Expected an instruction and an argument")),
+    };
+
+    let arg = if let Ok(offset) = arg.parse() {
+        Arg::Offset(offset)
+    } else if is_label(arg) && ins_type != InstructionType::Acc {
+        Arg::Label(arg)
+    } else if is_label(arg) {
+        return Err(anyhow!("`{}` takes a number, not a label", ins_type));
+    } else {
+        return Err(anyhow!("Expected a number or a label, found `{}`", arg));
+    };
+
+    Ok((ins_type, arg))
+}
+
+/// Write a program out as assembly source, with a label at every address a
+/// `jmp` goes to, and each instruction's address in a comment. Jumps to a
+/// label also have their offset in the comment.
+///
+/// Assembling the result gives back the same program.
+pub fn disassemble(program: &[Instruction]) -> String {
+    // Every jump target in the program, or just past its end
+    let labels = program
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| instruction.ins_type == InstructionType::Jmp)
+        .filter_map(|(addr, instruction)| {
+            let target = instruction.next_addr(addr as i64).ok()?;
+
+            if target < 0 || target > program.len() as i64 {
+                return None;
+            }
+
+            let name = if target == program.len() as i64 {
+                "end".to_string()
+            } else {
+                format!("L{}", target)
+            };
+
+            Some((target as usize, name))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut source = String::new();
+
+    for (addr, instruction) in program.iter().enumerate() {
+        let label = labels
+            .get(&addr)
+            .map(|name| format!("{}:", name))
+            .unwrap_or_default();
+
+        let target = instruction
+            .next_addr(addr as i64)
+            .ok()
+            .filter(|_| instruction.ins_type == InstructionType::Jmp)
+            .and_then(|target| usize::try_from(target).ok())
+            .and_then(|target| labels.get(&target));
+
+        let (text, comment) = match target {
+            Some(name) => (
+                format!("{} {}", instruction.ins_type, name),
+                format!("{}, {:+}", addr, instruction.arg),
+            ),
+            None => (instruction.to_string(), addr.to_string()),
+        };
+
+        source += &format!("{:<8}{:<12}; {}\n", label, text, comment);
+    }
+
+    if let Some(name) = labels.get(&program.len()) {
+        source += &format!("{}:\n", name);
+    }
+
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::parse_program;
+
+    #[test]
+    fn disassemble_then_assemble() {
+        // Jumps into the program, to just past its end, and out of range both
+        // ways
+        let program =
+            parse_program("nop +0\njmp +3\nacc -7\njmp -2\njmp +4\njmp -9\nnop +4\njmp +9")
+                .unwrap();
+        let source = disassemble(&program);
+
+        assert!(source.contains("jmp L4"));
+        assert!(source.contains("jmp end"));
+        assert!(source.ends_with("end:\n"));
+        assert_eq!(assemble(&source).unwrap(), program);
+    }
+
+    #[test]
+    fn labels() {
+        let source = "start:\n  nop start\nloop: acc +1\n  jmp loop\na: b: jmp a\n";
+
+        assert_eq!(
+            assemble(source).unwrap(),
+            parse_program("nop -0\nacc +1\njmp -1\njmp +0").unwrap()
+        );
+    }
+
+    #[test]
+    fn comments_and_whitespace() {
+        let source = "\n   acc   +3   ; add three\n# a whole line\n\n\tjmp\t-1#back\n  ;\n";
+
+        assert_eq!(
+            assemble(source).unwrap(),
+            parse_program("acc +3\njmp -1").unwrap()
+        );
+    }
+
+    #[test]
+    fn duplicate_label() {
+        let err = assemble("a: nop +0\na: acc +1").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Label `a` on line 2 was already defined on line 1"
+        );
+    }
+
+    #[test]
+    fn undefined_label() {
+        let err = assemble("nop +0\njmp nowhere").unwrap_err();
+
+        assert_eq!(err.to_string(), "Undefined label `nowhere` on line 2");
+    }
+
+    #[test]
+    fn label_as_acc_argument() {
+        let err = assemble("a: acc a").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", err),
+            "Could not assemble line 1: a: acc a: `acc` takes a number, not a label"
+        );
+    }
+}
@@ -0,0 +1,461 @@
+//! Control-flow analysis of a program without running it.
+//!
+//! The program is split into basic blocks: runs of instructions that are only
+//! entered at the top and only left at the bottom. A block starts at the first
+//! instruction, at every `jmp` target, and after every `jmp`. Since there are
+//! no conditional jumps, every block has exactly one successor, so the path the
+//! program takes from its first block is fixed, and following it either
+//! reaches the end of the program, leaves the program somewhere else, or comes
+//! back to a block it's already been through.
+
+use super::{Instruction, InstructionType};
+use anyhow::Result;
+use std::{collections::BTreeSet, io::Write};
+
+/// Where control goes after a block.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Successor {
+    /// The block with this index
+    Block(usize),
+    /// Just past the last instruction, so the program terminates
+    Exit,
+    /// Somewhere outside the program
+    OutOfBounds(i64),
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct BasicBlock {
+    /// The address of the block's first instruction
+    pub start: usize,
+    /// The address just past the block's last instruction
+    pub end: usize,
+    pub successor: Successor,
+}
+
+/// What following the program's path from its first block shows.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Termination {
+    /// The program terminates after running these blocks, in order.
+    Terminates { path: Vec<usize> },
+    /// The program runs the blocks in `path`, then the blocks in `cycle`
+    /// forever. `path` ends with the first block of `cycle`.
+    Loops { path: Vec<usize>, cycle: Vec<usize> },
+    /// The program runs the blocks in `path`, then jumps outside the program.
+    OutOfBounds { path: Vec<usize>, addr: i64 },
+}
+
+/// The control-flow graph of a program.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+    program: Vec<Instruction>,
+    blocks: Vec<BasicBlock>,
+    /// The block each instruction is in, indexed by address
+    block_of: Vec<usize>,
+    jump_targets: BTreeSet<usize>,
+}
+
+impl Cfg {
+    pub fn new(program: &[Instruction]) -> Result<Self> {
+        let len = program.len();
+
+        // Where each instruction goes next
+        let next_addrs = program
+            .iter()
+            .enumerate()
+            .map(|(addr, instruction)| instruction.next_addr(addr as i64))
+            .collect::<Result<Vec<_>>>()?;
+
+        let jumps = program
+            .iter()
+            .zip(&next_addrs)
+            .enumerate()
+            .filter(|(_, (instruction, _))| instruction.ins_type == InstructionType::Jmp)
+            .map(|(addr, (_, next))| (addr, *next));
+
+        let mut jump_targets = BTreeSet::new();
+        let mut leaders = BTreeSet::new();
+
+        if len > 0 {
+            leaders.insert(0);
+        }
+
+        for (addr, target) in jumps {
+            if target >= 0 && (target as usize) < len {
+                jump_targets.insert(target as usize);
+                leaders.insert(target as usize);
+            }
+
+            if addr + 1 < len {
+                leaders.insert(addr + 1);
+            }
+        }
+
+        let starts = leaders.into_iter().collect::<Vec<_>>();
+        let mut block_of = vec![0; len];
+
+        for (block, start) in starts.iter().enumerate() {
+            let end = starts.get(block + 1).copied().unwrap_or(len);
+            block_of[*start..end].iter_mut().for_each(|b| *b = block);
+        }
+
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(block, start)| {
+                let end = starts.get(block + 1).copied().unwrap_or(len);
+                let next = next_addrs[end - 1];
+
+                let successor = if next == len as i64 {
+                    Successor::Exit
+                } else if next >= 0 && (next as usize) < len {
+                    Successor::Block(block_of[next as usize])
+                } else {
+                    Successor::OutOfBounds(next)
+                };
+
+                BasicBlock {
+                    start: *start,
+                    end,
+                    successor,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            program: program.to_vec(),
+            blocks,
+            block_of,
+            jump_targets,
+        })
+    }
+
+    /// The basic blocks, in address order.
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+
+    /// The index of the block the instruction at `addr` is in.
+    pub fn block_of(&self, addr: usize) -> Option<usize> {
+        self.block_of.get(addr).copied()
+    }
+
+    /// Every address in the program that a `jmp` goes to.
+    pub fn jump_targets(&self) -> &BTreeSet<usize> {
+        &self.jump_targets
+    }
+
+    /// Every `jmp` that goes outside the program, other than to just past its
+    /// end, as (address, target).
+    pub fn out_of_bounds_jumps(&self) -> Vec<(usize, i64)> {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block.successor {
+                Successor::OutOfBounds(target) => Some((block.end - 1, target)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Follow the program's path from its first block to see whether it
+    /// terminates.
+    ///
+    /// Every block has exactly one successor, so this path is the only one
+    /// the program can take, and the result is a proof either way.
+    pub fn termination(&self) -> Termination {
+        let mut path = Vec::new();
+        let mut on_path = vec![false; self.blocks.len()];
+        let mut successor = match self.blocks.first() {
+            Some(_) => Successor::Block(0),
+            None => Successor::Exit,
+        };
+
+        loop {
+            match successor {
+                Successor::Exit => return Termination::Terminates { path },
+                Successor::OutOfBounds(addr) => return Termination::OutOfBounds { path, addr },
+                Successor::Block(block) if on_path[block] => {
+                    let cycle_start = path.iter().position(|b| *b == block).unwrap_or(0);
+                    let cycle = path[cycle_start..].to_vec();
+                    path.truncate(cycle_start + 1);
+
+                    return Termination::Loops { path, cycle };
+                }
+                Successor::Block(block) => {
+                    on_path[block] = true;
+                    path.push(block);
+                    successor = self.blocks[block].successor;
+                }
+            }
+        }
+    }
+
+    /// Every instruction that can never run, in address order.
+    pub fn unreachable(&self) -> Vec<usize> {
+        let reachable = match self.termination() {
+            Termination::Loops { path, cycle } => path.into_iter().chain(cycle).collect(),
+            Termination::Terminates { path } | Termination::OutOfBounds { path, .. } => path,
+        }
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !reachable.contains(i))
+            .flat_map(|(_, block)| block.start..block.end)
+            .collect()
+    }
+
+    /// The strongly connected components of the graph of blocks, each as a
+    /// sorted list of block indices, ordered by their first block. Found with
+    /// Tarjan's algorithm.
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        let n = self.blocks.len();
+        let successors = self
+            .blocks
+            .iter()
+            .map(|block| match block.successor {
+                Successor::Block(next) => Some(next),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut next_index = 0;
+        let mut index = vec![None; n];
+        let mut low_link = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut sccs = Vec::new();
+
+        for root in 0..n {
+            if index[root].is_some() {
+                continue;
+            }
+
+            // Blocks being visited, with whether their successor has been
+            // followed yet
+            let mut visiting = vec![(root, false)];
+            index[root] = Some(next_index);
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((block, followed)) = visiting.last().copied() {
+                if !followed {
+                    visiting.last_mut().unwrap().1 = true;
+
+                    if let Some(next) = successors[block] {
+                        match index[next] {
+                            None => {
+                                index[next] = Some(next_index);
+                                low_link[next] = next_index;
+                                next_index += 1;
+                                stack.push(next);
+                                on_stack[next] = true;
+                                visiting.push((next, false));
+                            }
+                            Some(next_idx) if on_stack[next] => {
+                                low_link[block] = low_link[block].min(next_idx);
+                            }
+                            Some(_) => {}
+                        }
+                    }
+
+                    continue;
+                }
+
+                visiting.pop();
+
+                if let Some((parent, _)) = visiting.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[block]);
+                }
+
+                if Some(low_link[block]) == index[block] {
+                    let mut scc = Vec::new();
+
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        scc.push(member);
+
+                        if member == block {
+                            break;
+                        }
+                    }
+
+                    scc.sort_unstable();
+                    sccs.push(scc);
+                }
+            }
+        }
+
+        sccs.sort();
+        sccs
+    }
+
+    /// The strongly connected components that are infinite loops: those with
+    /// more than one block, or a block that's its own successor.
+    ///
+    /// Every block has one successor, so each loop is a single cycle. Its
+    /// blocks are listed in the order they run, starting from the lowest.
+    pub fn loops(&self) -> Vec<Vec<usize>> {
+        self.sccs()
+            .into_iter()
+            .filter(|scc| {
+                scc.len() > 1 || self.blocks[scc[0]].successor == Successor::Block(scc[0])
+            })
+            .map(|scc| {
+                let mut cycle = vec![scc[0]];
+
+                while let Successor::Block(next) = self.blocks[cycle[cycle.len() - 1]].successor {
+                    if next == scc[0] {
+                        break;
+                    }
+
+                    cycle.push(next);
+                }
+
+                cycle
+            })
+            .collect()
+    }
+
+    /// Write the graph as a Graphviz `digraph`, with each block's
+    /// instructions in its node. Blocks on infinite loops are drawn in red,
+    /// and blocks that can never run are greyed out.
+    pub fn write_dot<W: Write>(&self, mut writer: W) -> Result<()> {
+        let unreachable = self
+            .unreachable()
+            .into_iter()
+            .filter_map(|addr| self.block_of(addr))
+            .collect::<BTreeSet<_>>();
+        let on_loop = self.loops().into_iter().flatten().collect::<BTreeSet<_>>();
+
+        writeln!(writer, "digraph cfg {{")?;
+        writeln!(writer, "    node [shape=box, fontname=\"monospace\"];")?;
+        writeln!(writer, "    exit [shape=doublecircle];")?;
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            let label = (block.start..block.end)
+                .map(|addr| format!("{}: {}\\l", addr, self.program[addr]))
+                .collect::<String>();
+
+            let style = if unreachable.contains(&i) {
+                ", style=dashed, fontcolor=gray, color=gray"
+            } else if on_loop.contains(&i) {
+                ", color=red"
+            } else {
+                ""
+            };
+
+            writeln!(writer, "    b{} [label=\"{}\"{}];", i, label, style)?;
+
+            match block.successor {
+                Successor::Block(next) => writeln!(writer, "    b{} -> b{};", i, next)?,
+                Successor::Exit => writeln!(writer, "    b{} -> exit;", i)?,
+                Successor::OutOfBounds(addr) => {
+                    writeln!(
+                        writer,
+                        "    oob{} [label=\"out of bounds: {}\", shape=octagon, color=red];",
+                        i, addr
+                    )?;
+                    writeln!(writer, "    b{} -> oob{};", i, i)?;
+                }
+            }
+        }
+
+        writeln!(writer, "}}")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::parse_program;
+
+    fn cfg(source: &str) -> Cfg {
+        Cfg::new(&parse_program(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn empty_program() {
+        let cfg = cfg("");
+
+        assert!(cfg.blocks().is_empty());
+        assert_eq!(cfg.termination(), Termination::Terminates { path: vec![] });
+        assert!(cfg.unreachable().is_empty());
+        assert!(cfg.loops().is_empty());
+    }
+
+    #[test]
+    fn jump_to_itself() {
+        let cfg = cfg("jmp +0\nacc +1");
+
+        assert_eq!(cfg.blocks()[0].successor, Successor::Block(0));
+        assert_eq!(
+            cfg.termination(),
+            Termination::Loops {
+                path: vec![0],
+                cycle: vec![0]
+            }
+        );
+        assert_eq!(cfg.unreachable(), [1]);
+        assert_eq!(cfg.loops(), [vec![0]]);
+    }
+
+    #[test]
+    fn out_of_bounds_jump() {
+        let cfg = cfg("acc +1\njmp -2\nnop +9\njmp +2");
+
+        assert_eq!(cfg.out_of_bounds_jumps(), [(1, -1), (3, 5)]);
+        assert_eq!(
+            cfg.termination(),
+            Termination::OutOfBounds {
+                path: vec![0],
+                addr: -1
+            }
+        );
+        assert_eq!(cfg.unreachable(), [2, 3]);
+        assert!(cfg.loops().is_empty());
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let cfg = cfg("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6");
+        let starts = cfg.blocks().iter().map(|b| b.start).collect::<Vec<_>>();
+
+        assert_eq!(starts, [0, 1, 3, 5, 6, 8]);
+        assert_eq!(
+            cfg.jump_targets().iter().copied().collect::<Vec<_>>(),
+            [1, 3, 6]
+        );
+        assert_eq!(cfg.block_of(7), Some(4));
+        assert_eq!(
+            cfg.termination(),
+            Termination::Loops {
+                path: vec![0, 1],
+                cycle: vec![1, 4, 2]
+            }
+        );
+        assert_eq!(cfg.unreachable(), [5, 8]);
+        assert_eq!(cfg.loops(), [vec![1, 4, 2]]);
+    }
+
+    #[test]
+    fn terminates() {
+        // The puzzle example with the `jmp` at 7 swapped for a `nop`
+        let cfg = cfg("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\nnop -4\nacc +6");
+
+        assert_eq!(
+            cfg.termination(),
+            Termination::Terminates {
+                path: vec![0, 1, 4]
+            }
+        );
+        assert_eq!(cfg.blocks()[4].successor, Successor::Exit);
+        assert_eq!(cfg.unreachable(), [3, 4, 5]);
+        assert!(cfg.loops().is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+//! Finding every single-instruction fix that makes a program terminate.
+//!
+//! Every instruction has exactly one successor, so the addresses from which
+//! the program runs to its end form a tree rooted at the address just past the
+//! last instruction, found by walking the edges backwards from there. Flipping
+//! the `jmp` or `nop` at `addr` on the original run's path fixes the program
+//! exactly when the flipped instruction's new successor is in that tree, and
+//! the path from it to the end doesn't come back through `addr`.
+
+use super::{Instruction, InstructionType};
+use anyhow::{anyhow, Result};
+
+/// A `jmp` swapped for a `nop`, or the other way around, that makes the
+/// program terminate.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Repair {
+    pub addr: usize,
+    /// The instruction that replaces the one at `addr`
+    pub instruction: Instruction,
+    /// The accumulator once the repaired program terminates
+    pub accumulator: i64,
+}
+
+/// Every flip of a single `jmp` or `nop` that makes `program` terminate, in
+/// address order. Takes time linear in the length of the program.
+pub fn repairs(program: &[Instruction]) -> Result<Vec<Repair>> {
+    let end = program.len();
+
+    // The address each instruction runs next, if that's in the program or
+    // just past its end
+    let successors = program
+        .iter()
+        .enumerate()
+        .map(|(addr, instruction)| Ok(in_bounds(instruction.next_addr(addr as i64)?, end)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let tree = TerminationTree::new(program, &successors);
+
+    // Run the original program, noting the accumulator before each
+    // instruction on its path
+    let mut acc_before = vec![None; end];
+    let mut addr = Some(0);
+    let mut accumulator = 0i64;
+
+    while let Some(a) = addr.filter(|a| *a < end && acc_before[*a].is_none()) {
+        acc_before[a] = Some(accumulator);
+        accumulator = checked_acc(accumulator, &program[a])?;
+        addr = successors[a];
+    }
+
+    let terminates = addr == Some(end);
+    let mut repairs = Vec::new();
+
+    for (addr, instruction) in program.iter().enumerate() {
+        if instruction.ins_type == InstructionType::Acc {
+            continue;
+        }
+
+        let flipped = instruction.swap_nop_and_jmp();
+
+        let accumulator = match acc_before[addr] {
+            // Off the original path, the flip changes nothing
+            None if terminates => Some(accumulator),
+            None => None,
+
+            Some(before) => match in_bounds(flipped.next_addr(addr as i64)?, end) {
+                Some(next) if tree.reaches_end(next) && !tree.passes_through(next, addr) => {
+                    let accumulator = tree.acc_to_end[next]
+                        .and_then(|acc_to_end| before.checked_add(acc_to_end))
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Accumulator overflowed flipping the instruction at {}",
+                                addr
+                            )
+                        })?;
+
+                    Some(accumulator)
+                }
+                _ => None,
+            },
+        };
+
+        if let Some(accumulator) = accumulator {
+            repairs.push(Repair {
+                addr,
+                instruction: flipped,
+                accumulator,
+            });
+        }
+    }
+
+    Ok(repairs)
+}
+
+/// The addresses that run to the end of the program, as a tree where each
+/// address's parent is its successor.
+struct TerminationTree {
+    /// When each address was entered and left in a depth-first walk of the
+    /// tree, or `None` if it isn't in the tree
+    visits: Vec<Option<(usize, usize)>>,
+    /// How much the accumulator changes between each address and the end, or
+    /// `None` if that overflows
+    acc_to_end: Vec<Option<i64>>,
+}
+
+impl TerminationTree {
+    fn new(program: &[Instruction], successors: &[Option<usize>]) -> Self {
+        let end = program.len();
+        let mut predecessors = vec![Vec::new(); end + 1];
+
+        for (addr, successor) in successors.iter().enumerate() {
+            if let Some(successor) = successor {
+                predecessors[*successor].push(addr);
+            }
+        }
+
+        let mut visits = vec![None; end + 1];
+        let mut acc_to_end = vec![Some(0); end + 1];
+        let mut clock = 0;
+
+        // Each address with whether its predecessors have been pushed yet
+        let mut stack = vec![(end, false)];
+
+        while let Some((addr, expanded)) = stack.pop() {
+            if expanded {
+                if let Some((entered, _)) = visits[addr] {
+                    visits[addr] = Some((entered, clock));
+                }
+
+                continue;
+            }
+
+            visits[addr] = Some((clock, clock));
+            clock += 1;
+
+            if let Some(successor) = successors.get(addr).copied().flatten() {
+                acc_to_end[addr] =
+                    acc_to_end[successor].and_then(|acc| checked_acc(acc, &program[addr]).ok());
+            }
+
+            stack.push((addr, true));
+            stack.extend(predecessors[addr].iter().map(|p| (*p, false)));
+        }
+
+        Self { visits, acc_to_end }
+    }
+
+    fn reaches_end(&self, addr: usize) -> bool {
+        self.visits[addr].is_some()
+    }
+
+    /// Whether the path from `from` to the end runs the instruction at `addr`.
+    fn passes_through(&self, from: usize, addr: usize) -> bool {
+        match (self.visits[from], self.visits[addr]) {
+            (Some((from_in, from_out)), Some((addr_in, addr_out))) => {
+                addr_in <= from_in && from_out <= addr_out
+            }
+            _ => false,
+        }
+    }
+}
+
+fn in_bounds(addr: i64, end: usize) -> Option<usize> {
+    Some(addr as usize).filter(|_| addr >= 0 && addr as usize <= end)
+}
+
+fn checked_acc(accumulator: i64, instruction: &Instruction) -> Result<i64> {
+    match instruction.ins_type {
+        InstructionType::Acc => accumulator
+            .checked_add(instruction.arg)
+            .ok_or_else(|| anyhow!("Accumulator overflowed running `{}`", instruction)),
+        _ => Ok(accumulator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::parse_program;
+
+    fn repairs_of(source: &str) -> Vec<Repair> {
+        repairs(&parse_program(source).unwrap()).unwrap()
+    }
+
+    fn repair(addr: usize, instruction: &str, accumulator: i64) -> Repair {
+        Repair {
+            addr,
+            instruction: instruction.parse().unwrap(),
+            accumulator,
+        }
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let source = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+        assert_eq!(repairs_of(source), [repair(7, "nop -4", 8)]);
+    }
+
+    #[test]
+    fn already_terminates() {
+        // Flipping the `jmp` at 0 runs every instruction, and the `nop` at 1
+        // isn't on the path, so flipping it changes nothing
+        assert_eq!(
+            repairs_of("jmp +2\nnop +5\nacc +1"),
+            [repair(0, "nop +2", 1), repair(1, "jmp +5", 1)]
+        );
+
+        // Flipping the `nop` at 0 loops forever
+        assert_eq!(repairs_of("nop +0\nacc +3"), []);
+    }
+
+    #[test]
+    fn flip_leads_back_through_itself() {
+        // Flipping the `nop` at 1 goes back to 0, which runs into it again
+        assert_eq!(
+            repairs_of("acc +1\nnop -1\nacc +2\njmp +1"),
+            [repair(3, "nop +1", 3)]
+        );
+    }
+
+    #[test]
+    fn no_repair() {
+        assert_eq!(repairs_of("jmp +0\njmp -1"), []);
+    }
+
+    #[test]
+    fn empty_program() {
+        assert_eq!(repairs_of(""), []);
+    }
+}
@@ -0,0 +1,220 @@
+//! Recording every step a program takes, and comparing recordings.
+//!
+//! A trace is written as JSON lines, one object per step:
+//!
+//! ```text
+//! {"addr":0,"instruction":"acc +45","acc_before":0,"acc_after":45}
+//! ```
+
+use super::{Halt, Instruction, Vm};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// One instruction run by the VM.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Step {
+    pub addr: usize,
+    #[serde(with = "as_text")]
+    pub instruction: Instruction,
+    pub acc_before: i64,
+    pub acc_after: i64,
+}
+
+/// Writes each step a VM takes to a JSON lines trace.
+pub struct Recorder<W: Write> {
+    writer: W,
+    steps: usize,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, steps: 0 }
+    }
+
+    /// The number of steps recorded so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Run the VM's next instruction, like [`Vm::step`], and record it.
+    pub fn step(&mut self, vm: &mut Vm) -> Result<Option<Halt>> {
+        let addr = vm.addr();
+        let instruction = vm.current_instruction().copied();
+        let acc_before = vm.accumulator();
+
+        if let Some(halt) = vm.step()? {
+            return Ok(Some(halt));
+        }
+
+        if let (Some(addr), Some(instruction)) = (addr, instruction) {
+            let step = Step {
+                addr,
+                instruction,
+                acc_before,
+                acc_after: vm.accumulator(),
+            };
+
+            serde_json::to_writer(&mut self.writer, &step)?;
+            writeln!(self.writer)?;
+            self.steps += 1;
+        }
+
+        Ok(None)
+    }
+
+    /// Run the VM until it halts, like [`Vm::run`], recording every step.
+    pub fn run(&mut self, vm: &mut Vm) -> Result<Halt> {
+        loop {
+            if let Some(halt) = self.step(vm)? {
+                self.writer.flush()?;
+                return Ok(halt);
+            }
+        }
+    }
+}
+
+/// Read a JSON lines trace.
+pub fn read_trace<R: BufRead>(reader: R) -> Result<Vec<Step>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(line_num, line)| {
+            serde_json::from_str(&line?)
+                .with_context(|| format!("Could not parse step on line {}", line_num + 1))
+        })
+        .collect()
+}
+
+/// The index of the first step where two traces ran different instructions,
+/// or where one of them ended before the other. `None` if they're the same.
+pub fn path_divergence(a: &[Step], b: &[Step]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(a, b)| a.addr != b.addr || a.instruction != b.instruction)
+        .or_else(|| Some(a.len().min(b.len())).filter(|_| a.len() != b.len()))
+}
+
+/// The index of the first step after which two traces' accumulators differ.
+/// Only steps that both traces have are compared.
+pub fn accumulator_divergence(a: &[Step], b: &[Step]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(a, b)| a.acc_after != b.acc_after)
+}
+
+/// Serializing instructions as they're written in a program, e.g. `acc +5`.
+mod as_text {
+    use super::Instruction;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        instruction: &Instruction,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(instruction)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Instruction, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::parse_program;
+
+    fn step(addr: usize, instruction: &str, acc_before: i64, acc_after: i64) -> Step {
+        Step {
+            addr,
+            instruction: instruction.parse().unwrap(),
+            acc_before,
+            acc_after,
+        }
+    }
+
+    #[test]
+    fn record_and_read_back() -> Result<()> {
+        let program = parse_program(
+            "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6",
+        )?;
+        let mut vm = Vm::new(program);
+        let mut recorder = Recorder::new(Vec::new());
+
+        assert_eq!(recorder.run(&mut vm)?, Halt::LoopDetected { addr: 1 });
+        assert_eq!(recorder.steps(), 7);
+
+        let output = String::from_utf8(recorder.writer.clone())?;
+        assert_eq!(
+            output.lines().next(),
+            Some(r#"{"addr":0,"instruction":"nop +0","acc_before":0,"acc_after":0}"#)
+        );
+
+        assert_eq!(
+            read_trace(output.as_bytes())?,
+            [
+                step(0, "nop +0", 0, 0),
+                step(1, "acc +1", 0, 1),
+                step(2, "jmp +4", 1, 1),
+                step(6, "acc +1", 1, 2),
+                step(7, "jmp -4", 2, 2),
+                step(3, "acc +3", 2, 5),
+                step(4, "jmp -3", 5, 5),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_trace_errors() {
+        let trace =
+            "\n{\"addr\":0,\"instruction\":\"nop +0\",\"acc_before\":0,\"acc_after\":0}\n\n\
+                     {\"addr\":1,\"instruction\":\"mul +2\",\"acc_before\":0,\"acc_after\":0}\n";
+        let err = read_trace(trace.as_bytes()).unwrap_err();
+
+        assert_eq!(err.to_string(), "Could not parse step on line 4");
+    }
+
+    #[test]
+    fn divergence() {
+        let a = [
+            step(0, "acc +1", 0, 1),
+            step(1, "jmp +2", 1, 1),
+            step(3, "acc +2", 1, 3),
+        ];
+        let same_path = [
+            step(0, "acc +1", 0, 1),
+            step(1, "jmp +2", 1, 1),
+            step(3, "acc +2", 1, 4),
+        ];
+        let fork = [
+            step(0, "acc +1", 0, 1),
+            step(1, "nop +2", 1, 1),
+            step(2, "acc +5", 1, 6),
+        ];
+
+        assert_eq!(path_divergence(&a, &a), None);
+        assert_eq!(accumulator_divergence(&a, &a), None);
+
+        assert_eq!(path_divergence(&a, &same_path), None);
+        assert_eq!(accumulator_divergence(&a, &same_path), Some(2));
+
+        assert_eq!(path_divergence(&a, &fork), Some(1));
+        assert_eq!(accumulator_divergence(&a, &fork), Some(2));
+
+        // A trace that stops early diverges where it ends, but its
+        // accumulators only differ where both traces have steps
+        assert_eq!(path_divergence(&a, &a[..2]), Some(2));
+        assert_eq!(path_divergence(&a[..1], &a), Some(1));
+        assert_eq!(accumulator_divergence(&a, &a[..2]), None);
+        assert_eq!(path_divergence(&[], &[]), None);
+        assert_eq!(path_divergence(&[], &a), Some(0));
+    }
+}
@@ -0,0 +1,598 @@
+//! Customs declaration answers, as used on day 6.
+//!
+//! A declarations file is a list of groups separated by blank lines. Each line
+//! of a group is one person, and each character on the line is a question that
+//! person answered "yes" to. Which characters are questions is set by an
+//! [`Alphabet`], which is `a` to `z` by default and can have at most
+//! [`MAX_QUESTIONS`] (128) questions, since each [`AnswerSet`] is a `u128`.
+
+pub mod stats;
+
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    ops::{BitAnd, BitOr, BitXor},
+    str::FromStr,
+};
+
+/// The most questions an [`Alphabet`] can have.
+pub const MAX_QUESTIONS: usize = 128;
+
+lazy_static! {
+    static ref DEFAULT_ALPHABET: Alphabet =
+        Alphabet::new('a'..='z').expect("`a` to `z` is a valid alphabet");
+}
+
+/// The characters that are questions, in order. The question at index `i` is
+/// stored as bit `i` of an [`AnswerSet`].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Alphabet {
+    questions: Vec<char>,
+    /// The index of each ASCII question, by character code, so that the usual
+    /// alphabets never need a hash lookup
+    ascii_indices: [Option<u8>; 128],
+    non_ascii_indices: HashMap<char, usize>,
+}
+
+impl Default for Alphabet {
+    /// The questions `a` to `z`.
+    fn default() -> Self {
+        DEFAULT_ALPHABET.clone()
+    }
+}
+
+impl Alphabet {
+    /// Create an alphabet of up to [`MAX_QUESTIONS`] distinct, non-whitespace
+    /// characters.
+    pub fn new<I: IntoIterator<Item = char>>(questions: I) -> Result<Self> {
+        let questions = questions.into_iter().collect::<Vec<_>>();
+
+        if questions.is_empty() || questions.len() > MAX_QUESTIONS {
+            return Err(anyhow!(
+                "Alphabets must have 1 to {} questions, found {}",
+                MAX_QUESTIONS,
+                questions.len()
+            ));
+        }
+
+        let mut ascii_indices = [None; 128];
+        let mut non_ascii_indices = HashMap::new();
+
+        for (i, question) in questions.iter().enumerate() {
+            if question.is_whitespace() {
+                return Err(anyhow!(
+                    "Questions can't be whitespace, found {:?}",
+                    question
+                ));
+            }
+
+            let seen = if question.is_ascii() {
+                // `i` fits in a `u8`, since there are at most `MAX_QUESTIONS`
+                ascii_indices[*question as usize].replace(i as u8).is_some()
+            } else {
+                non_ascii_indices.insert(*question, i).is_some()
+            };
+
+            if seen {
+                return Err(anyhow!("Question {:?} appears more than once", question));
+            }
+        }
+
+        Ok(Self {
+            questions,
+            ascii_indices,
+            non_ascii_indices,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+
+    /// The questions, in order.
+    pub fn questions(&self) -> &[char] {
+        &self.questions
+    }
+
+    /// The index of a question, if it's in the alphabet.
+    pub fn index_of(&self, question: char) -> Option<usize> {
+        match self.ascii_indices.get(question as usize) {
+            Some(index) => index.map(usize::from),
+            None => self.non_ascii_indices.get(&question).copied(),
+        }
+    }
+
+    /// The set of every question in the alphabet.
+    pub fn all(&self) -> AnswerSet {
+        AnswerSet((0..self.len()).fold(0, |bits, i| bits | 1 << i))
+    }
+
+    /// Parse one person's line of answers, rejecting anything that isn't a
+    /// question.
+    pub fn parse(&self, line: &str) -> Result<AnswerSet> {
+        let mut set = AnswerSet::EMPTY;
+
+        for (i, c) in line.chars().enumerate() {
+            let index = self
+                .index_of(c)
+                .ok_or_else(|| anyhow!("Expected a question, found {:?} at column {}", c, i + 1))?;
+
+            set.insert_index(index);
+        }
+
+        Ok(set)
+    }
+
+    /// Write out the questions in a set, in alphabet order.
+    pub fn format(&self, set: AnswerSet) -> String {
+        set.iter().filter_map(|i| self.questions.get(i)).collect()
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = anyhow::Error;
+
+    /// Parse an alphabet written as characters and ranges of characters, e.g.
+    /// `a-z` or `a-zA-Z0-9!?`. A `-` at the start or end stands for itself.
+    fn from_str(s: &str) -> Result<Self> {
+        let chars = s.chars().collect::<Vec<_>>();
+        let mut questions = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if i + 2 < chars.len() && chars[i + 1] == '-' {
+                if chars[i] > chars[i + 2] {
+                    return Err(anyhow!("Range {}-{} is backwards", chars[i], chars[i + 2]));
+                }
+
+                questions.extend(chars[i]..=chars[i + 2]);
+                i += 3;
+            } else {
+                questions.push(chars[i]);
+                i += 1;
+            }
+        }
+
+        Self::new(questions)
+    }
+}
+
+/// A set of questions, stored as a bitmask where bit `i` is set if the
+/// question at index `i` of the [`Alphabet`] is in the set.
+///
+/// `FromStr` and `Display` use the default `a` to `z` alphabet.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Default)]
+pub struct AnswerSet(u128);
+
+impl AnswerSet {
+    /// No questions.
+    pub const EMPTY: Self = Self(0);
+
+    /// Every possible question, in any alphabet.
+    pub const ALL: Self = Self(u128::MAX);
+
+    /// Add a question to the set by its index, which must be less than
+    /// [`MAX_QUESTIONS`].
+    pub fn insert(&mut self, index: usize) -> Result<()> {
+        if index >= MAX_QUESTIONS {
+            return Err(anyhow!(
+                "Question index {} is out of range; a set holds at most {} questions",
+                index,
+                MAX_QUESTIONS
+            ));
+        }
+
+        self.insert_index(index);
+
+        Ok(())
+    }
+
+    /// Add a question by an index known to be in range, such as one from an
+    /// [`Alphabet`].
+    fn insert_index(&mut self, index: usize) {
+        self.0 |= 1 << index;
+    }
+
+    /// Check for a question in the set by its index.
+    pub fn contains(&self, index: usize) -> bool {
+        index < MAX_QUESTIONS && self.0 >> index & 1 == 1
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+
+    /// The number of questions in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The indices of the questions in the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        let bits = self.0;
+
+        (0..MAX_QUESTIONS).filter(move |i| bits >> i & 1 == 1)
+    }
+}
+
+impl BitOr for AnswerSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for AnswerSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl BitXor for AnswerSet {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        self.symmetric_difference(rhs)
+    }
+}
+
+impl FromStr for AnswerSet {
+    type Err = anyhow::Error;
+
+    /// Parse one person's line of answers.
+    fn from_str(s: &str) -> Result<Self> {
+        DEFAULT_ALPHABET.parse(s)
+    }
+}
+
+impl Display for AnswerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", DEFAULT_ALPHABET.format(*self))
+    }
+}
+
+/// The answers of one group of people.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Group {
+    /// The questions each person in the group answered "yes" to
+    pub people: Vec<AnswerSet>,
+}
+
+impl Group {
+    /// How many people answered "yes" to each question, by index.
+    pub fn counts(&self) -> [usize; MAX_QUESTIONS] {
+        let mut counts = [0; MAX_QUESTIONS];
+
+        for person in &self.people {
+            for i in person.iter() {
+                counts[i] += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// The questions selected by combining everyone's answers in some way.
+    pub fn combine(&self, combine: Combine) -> AnswerSet {
+        match combine {
+            Combine::Union => self.union(),
+            Combine::Intersection => self.intersection(),
+            Combine::SymmetricDifference => self.symmetric_difference(),
+            Combine::AtLeast(k) => self.at_least(k),
+        }
+    }
+
+    /// Questions anyone in the group answered "yes" to.
+    pub fn union(&self) -> AnswerSet {
+        self.people.iter().fold(AnswerSet::EMPTY, |acc, p| acc | *p)
+    }
+
+    /// Questions everyone in the group answered "yes" to.
+    pub fn intersection(&self) -> AnswerSet {
+        if self.people.is_empty() {
+            return AnswerSet::EMPTY;
+        }
+
+        self.people.iter().fold(AnswerSet::ALL, |acc, p| acc & *p)
+    }
+
+    /// Questions an odd number of people in the group answered "yes" to.
+    pub fn symmetric_difference(&self) -> AnswerSet {
+        self.people.iter().fold(AnswerSet::EMPTY, |acc, p| acc ^ *p)
+    }
+
+    /// Questions at least `k` people in the group answered "yes" to. A `k` of 0
+    /// is treated as 1, so it gives the same questions as [`Group::union`]
+    /// rather than every question in the alphabet.
+    pub fn at_least(&self, k: usize) -> AnswerSet {
+        let mut set = AnswerSet::EMPTY;
+
+        for (i, count) in self.counts().iter().enumerate() {
+            if *count >= k.max(1) {
+                set.insert_index(i);
+            }
+        }
+
+        set
+    }
+}
+
+/// A way of combining the answers of everyone in a group.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Combine {
+    /// Questions anyone answered "yes" to (part 1).
+    Union,
+    /// Questions everyone answered "yes" to (part 2).
+    Intersection,
+    /// Questions an odd number of people answered "yes" to. For two people,
+    /// this is the questions only one of them answered "yes" to.
+    SymmetricDifference,
+    /// Questions at least this many people answered "yes" to. Parsing rejects
+    /// 0; see [`Group::at_least`] for what it means here.
+    AtLeast(usize),
+}
+
+impl FromStr for Combine {
+    type Err = anyhow::Error;
+
+    /// Parse one of `union`, `intersection`, `symmetric-difference` or
+    /// `at-least-<k>`, where `k` is at least 1.
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "union" => Ok(Self::Union),
+            "intersection" => Ok(Self::Intersection),
+            "symmetric-difference" => Ok(Self::SymmetricDifference),
+            _ => match s.strip_prefix("at-least-") {
+                Some(k) => match k.parse()? {
+                    0 => Err(anyhow!(
+                        "`at-least-0` isn't allowed; use `at-least-1` or `union`"
+                    )),
+                    k => Ok(Self::AtLeast(k)),
+                },
+                None => Err(anyhow!("Unknown way of combining answers `{}`", s)),
+            },
+        }
+    }
+}
+
+impl Display for Combine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Union => f.pad("union"),
+            Self::Intersection => f.pad("intersection"),
+            Self::SymmetricDifference => f.pad("symmetric-difference"),
+            Self::AtLeast(k) => f.pad(&format!("at-least-{}", k)),
+        }
+    }
+}
+
+/// What to do with characters that aren't questions in the alphabet.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Strictness {
+    /// Stop at the first character that isn't a question.
+    Reject,
+    /// Replace characters with their lower- or uppercase form if that's a
+    /// question, and drop them otherwise.
+    Normalize,
+}
+
+/// A character that wasn't a question, found while parsing with
+/// [`Strictness::Normalize`].
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Irregularity {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    pub found: char,
+    /// The question the character was replaced with, or `None` if it was
+    /// dropped
+    pub replacement: Option<char>,
+}
+
+impl Display for Irregularity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {:?} is not a question",
+            self.line, self.column, self.found
+        )?;
+
+        match self.replacement {
+            Some(replacement) => write!(f, ", read as {:?}", replacement),
+            None => write!(f, ", dropped"),
+        }
+    }
+}
+
+/// Reads declarations files with some alphabet and strictness.
+#[derive(Debug, Clone)]
+pub struct Parser {
+    pub alphabet: Alphabet,
+    pub strictness: Strictness,
+}
+
+impl Default for Parser {
+    /// Reads questions `a` to `z`, rejecting anything else.
+    fn default() -> Self {
+        Self {
+            alphabet: Alphabet::default(),
+            strictness: Strictness::Reject,
+        }
+    }
+}
+
+impl Parser {
+    /// Parse a declarations file into its groups, along with any characters
+    /// that had to be normalized.
+    ///
+    /// Groups are separated by one or more blank lines. Each non-blank line is
+    /// one person. Answers are read straight into [`AnswerSet`]s, without
+    /// copying any of the text.
+    pub fn parse(&self, input: &str) -> Result<(Vec<Group>, Vec<Irregularity>)> {
+        let mut groups = Vec::new();
+        let mut group = Group::default();
+        let mut irregularities = Vec::new();
+
+        for (line_num, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                if !group.people.is_empty() {
+                    groups.push(std::mem::take(&mut group));
+                }
+
+                continue;
+            }
+
+            let mut person = AnswerSet::EMPTY;
+
+            for (col_num, c) in line.chars().enumerate() {
+                if let Some(index) = self.alphabet.index_of(c) {
+                    person.insert_index(index);
+                    continue;
+                }
+
+                if self.strictness == Strictness::Reject {
+                    return Err(anyhow!(
+                        "Expected a question on line {}, column {}, found {:?}",
+                        line_num + 1,
+                        col_num + 1,
+                        c,
+                    ));
+                }
+
+                let replacement = c
+                    .to_lowercase()
+                    .chain(c.to_uppercase())
+                    .find(|r| self.alphabet.index_of(*r).is_some());
+
+                if let Some(index) = replacement.and_then(|r| self.alphabet.index_of(r)) {
+                    person.insert_index(index);
+                }
+
+                irregularities.push(Irregularity {
+                    line: line_num + 1,
+                    column: col_num + 1,
+                    found: c,
+                    replacement,
+                });
+            }
+
+            group.people.push(person);
+        }
+
+        if !group.people.is_empty() {
+            groups.push(group);
+        }
+
+        Ok((groups, irregularities))
+    }
+}
+
+/// Parse a declarations file into its groups, with the questions `a` to `z`.
+/// Any other character is an error.
+pub fn parse_groups(input: &str) -> Result<Vec<Group>> {
+    Ok(Parser::default().parse(input)?.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alphabet_from_str() {
+        let alphabet = "a-c".parse::<Alphabet>().unwrap();
+        assert_eq!(alphabet.questions(), ['a', 'b', 'c']);
+
+        let alphabet = "-a-cx".parse::<Alphabet>().unwrap();
+        assert_eq!(alphabet.questions(), ['-', 'a', 'b', 'c', 'x']);
+        assert_eq!(alphabet.index_of('x'), Some(4));
+
+        let alphabet = "a-c-".parse::<Alphabet>().unwrap();
+        assert_eq!(alphabet.questions(), ['a', 'b', 'c', '-']);
+
+        assert_eq!(
+            "z-a".parse::<Alphabet>().unwrap_err().to_string(),
+            "Range z-a is backwards"
+        );
+        assert!("a-cb".parse::<Alphabet>().is_err());
+        assert!("".parse::<Alphabet>().is_err());
+        assert!("a c".parse::<Alphabet>().is_err());
+    }
+
+    #[test]
+    fn large_alphabet() {
+        let alphabet = "a-zA-Z0-9!?".parse::<Alphabet>().unwrap();
+        assert_eq!(alphabet.len(), 64);
+        assert_eq!(alphabet.index_of('?'), Some(63));
+        assert_eq!(alphabet.index_of('~'), None);
+
+        let set = alphabet.parse("?aZ9").unwrap();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 51, 61, 63]);
+        assert_eq!(alphabet.format(set), "aZ9?");
+
+        let unicode = "a-zé€".parse::<Alphabet>().unwrap();
+        assert_eq!(unicode.index_of('€'), Some(27));
+        assert_eq!(unicode.format(unicode.parse("€zé").unwrap()), "zé€");
+    }
+
+    #[test]
+    fn reject_reports_line_and_column() {
+        let err = Parser::default().parse("abc\n\nxYz").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected a question on line 3, column 2, found 'Y'"
+        );
+    }
+
+    #[test]
+    fn normalize_replaces_and_drops() {
+        let parser = Parser {
+            alphabet: Alphabet::default(),
+            strictness: Strictness::Normalize,
+        };
+        let (groups, irregularities) = parser.parse("aB\n\n\nc!\n").unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].people, ["ab".parse().unwrap()]);
+        assert_eq!(groups[1].people, ["c".parse().unwrap()]);
+        assert_eq!(
+            irregularities,
+            [
+                Irregularity {
+                    line: 1,
+                    column: 2,
+                    found: 'B',
+                    replacement: Some('b'),
+                },
+                Irregularity {
+                    line: 4,
+                    column: 2,
+                    found: '!',
+                    replacement: None,
+                },
+            ]
+        );
+    }
+}
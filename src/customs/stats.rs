@@ -0,0 +1,148 @@
+//! Statistics about the answers in a declarations file.
+
+use super::{Alphabet, Group};
+use anyhow::Result;
+use serde::Serialize;
+use std::{collections::BTreeMap, io::Write};
+
+/// How often one question was answered "yes" to.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct QuestionStats {
+    pub question: char,
+    /// The number of people who answered "yes"
+    pub people: usize,
+    /// The number of groups where anyone answered "yes"
+    pub groups: usize,
+    /// The number of groups where everyone answered "yes"
+    pub unanimous_groups: usize,
+}
+
+/// A summary of one group's answers.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct GroupStats {
+    /// The group's (1-based) position in the file
+    pub group: usize,
+    pub size: usize,
+    /// Questions anyone in the group answered "yes" to
+    pub anyone: String,
+    /// Questions everyone in the group answered "yes" to
+    pub everyone: String,
+}
+
+/// The number of groups of one size.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize)]
+pub struct SizeStats {
+    pub size: usize,
+    pub groups: usize,
+}
+
+/// Statistics about every question and group in a declarations file.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Report {
+    pub num_people: usize,
+    /// Every question, in alphabet order
+    pub questions: Vec<QuestionStats>,
+    /// Every group, in the order they appear in the file
+    pub groups: Vec<GroupStats>,
+    /// How many groups there are of each size, from smallest to largest
+    pub sizes: Vec<SizeStats>,
+}
+
+impl Report {
+    pub fn new(groups: &[Group], alphabet: &Alphabet) -> Self {
+        let mut questions = alphabet
+            .questions()
+            .iter()
+            .map(|question| QuestionStats {
+                question: *question,
+                people: 0,
+                groups: 0,
+                unanimous_groups: 0,
+            })
+            .collect::<Vec<_>>();
+        let mut sizes = BTreeMap::new();
+
+        for group in groups {
+            let anyone = group.union();
+            let everyone = group.intersection();
+            let counts = group.counts();
+
+            for (i, stats) in questions.iter_mut().enumerate() {
+                stats.people += counts[i];
+                stats.groups += anyone.contains(i) as usize;
+                stats.unanimous_groups += everyone.contains(i) as usize;
+            }
+
+            *sizes.entry(group.people.len()).or_insert(0) += 1;
+        }
+
+        Self {
+            num_people: groups.iter().map(|g| g.people.len()).sum(),
+            questions,
+            groups: groups
+                .iter()
+                .enumerate()
+                .map(|(i, group)| GroupStats {
+                    group: i + 1,
+                    size: group.people.len(),
+                    anyone: alphabet.format(group.union()),
+                    everyone: alphabet.format(group.intersection()),
+                })
+                .collect(),
+            sizes: sizes
+                .into_iter()
+                .map(|(size, groups)| SizeStats { size, groups })
+                .collect(),
+        }
+    }
+
+    /// The groups with the most people.
+    pub fn largest_groups(&self) -> Vec<&GroupStats> {
+        let max = self.groups.iter().map(|g| g.size).max();
+
+        self.groups.iter().filter(|g| Some(g.size) == max).collect()
+    }
+
+    /// The groups with the fewest people.
+    pub fn smallest_groups(&self) -> Vec<&GroupStats> {
+        let min = self.groups.iter().map(|g| g.size).min();
+
+        self.groups.iter().filter(|g| Some(g.size) == min).collect()
+    }
+
+    /// Groups of more than one person where there's no question everyone
+    /// answered "yes" to.
+    pub fn groups_without_agreement(&self) -> Vec<&GroupStats> {
+        self.groups
+            .iter()
+            .filter(|g| g.size > 1 && g.everyone.is_empty())
+            .collect()
+    }
+
+    /// Write the per-question statistics as CSV.
+    pub fn write_questions_csv<W: Write>(&self, writer: W) -> Result<()> {
+        write_csv(&self.questions, writer)
+    }
+
+    /// Write the per-group statistics as CSV.
+    pub fn write_groups_csv<W: Write>(&self, writer: W) -> Result<()> {
+        write_csv(&self.groups, writer)
+    }
+
+    /// Write the histogram of group sizes as CSV.
+    pub fn write_sizes_csv<W: Write>(&self, writer: W) -> Result<()> {
+        write_csv(&self.sizes, writer)
+    }
+}
+
+fn write_csv<T: Serialize, W: Write>(rows: &[T], writer: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,6 @@
+pub mod args;
+pub mod bags;
+pub mod console;
+pub mod customs;
+pub mod passport;
+pub mod seat;
@@ -0,0 +1,589 @@
+//! Reading of the passport batch files used on day 4.
+//!
+//! A batch is a sequence of passport records separated by blank lines. Each
+//! record is a whitespace-separated list of `key:value` fields, which may be
+//! spread over several lines.
+
+pub mod table;
+
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::{self, Display},
+    io::Write,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+/// The keys of all known passport fields, in the order they're described in.
+pub const KEYS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+/// A single `key:value` field of a passport record.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Field<'a> {
+    /// The field's key, e.g. `byr`
+    pub key: &'a str,
+    /// The field's value, e.g. `1937`
+    pub value: &'a str,
+    /// Byte offset of the field within the record it was read from
+    pub offset: usize,
+}
+
+/// Split a batch into its records.
+///
+/// Records are separated by one or more blank lines. Lines containing only
+/// whitespace (including a trailing `\r`) count as blank.
+pub fn records(batch: &str) -> impl Iterator<Item = &str> {
+    let mut records = Vec::new();
+    let mut record_start = None;
+    let mut offset = 0;
+
+    for line in batch.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(start) = record_start.take() {
+                records.push(batch[start..offset].trim());
+            }
+        } else if record_start.is_none() {
+            record_start = Some(offset);
+        }
+
+        offset += line.len();
+    }
+
+    if let Some(start) = record_start {
+        records.push(batch[start..].trim());
+    }
+
+    records.into_iter()
+}
+
+/// Split a record into its `key:value` fields.
+///
+/// Tokens without a `:`, or with an empty key or value, are reported as errors.
+/// Everything after the first `:` belongs to the value.
+pub fn fields(record: &str) -> impl Iterator<Item = Result<Field<'_>>> {
+    record
+        .split(char::is_whitespace)
+        .filter(|token| !token.is_empty())
+        .map(move |token| {
+            let offset = token.as_ptr() as usize - record.as_ptr() as usize;
+
+            match token.split_once(':') {
+                Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+                    Ok(Field { key, value, offset })
+                }
+
+                Some(_) => Err(anyhow!(
+                    "Malformed field `{}` at byte {}: key and value must both be non-empty",
+                    token,
+                    offset,
+                )),
+
+                None => Err(anyhow!(
+                    "Malformed field `{}` at byte {}: expected `key:value`",
+                    token,
+                    offset,
+                )),
+            }
+        })
+}
+
+/// A field value that is kept exactly as it was written.
+///
+/// Values in their canonical form are parsed into `T`. Anything else is kept
+/// verbatim, so that a passport can always be written back out unchanged.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Value<T> {
+    /// The value was parsed into its strongly typed form.
+    Parsed(T),
+    /// The value could not be parsed, and is kept as written.
+    Raw(String),
+}
+
+impl<T: FromStr + Display> Value<T> {
+    fn parse(s: &str) -> Self {
+        match s.parse::<T>() {
+            Ok(parsed) if parsed.to_string() == s => Self::Parsed(parsed),
+            _ => Self::Raw(s.to_string()),
+        }
+    }
+}
+
+impl<T> Value<T> {
+    /// Returns the parsed value, if there is one.
+    pub fn parsed(&self) -> Option<&T> {
+        match self {
+            Self::Parsed(parsed) => Some(parsed),
+            Self::Raw(_) => None,
+        }
+    }
+}
+
+impl<T: Display> Display for Value<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parsed(parsed) => write!(f, "{}", parsed),
+            Self::Raw(raw) => write!(f, "{}", raw),
+        }
+    }
+}
+
+/// A four-digit year.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct Year(pub u16);
+
+impl FromStr for Year {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.len() != 4 || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(anyhow!("Expected a four-digit year, found `{}`", s));
+        }
+
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.0)
+    }
+}
+
+/// The unit a height is measured in.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum HeightUnit {
+    /// Centimetres (`cm`)
+    Cm,
+    /// Inches (`in`)
+    In,
+}
+
+impl Display for HeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cm => write!(f, "cm"),
+            Self::In => write!(f, "in"),
+        }
+    }
+}
+
+/// A height with its unit, e.g. `183cm`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Height {
+    pub value: u32,
+    pub unit: HeightUnit,
+}
+
+impl FromStr for Height {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (value, unit) = if let Some(value) = s.strip_suffix("cm") {
+            (value, HeightUnit::Cm)
+        } else if let Some(value) = s.strip_suffix("in") {
+            (value, HeightUnit::In)
+        } else {
+            return Err(anyhow!("Expected a height in `cm` or `in`, found `{}`", s));
+        };
+
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(anyhow!("Expected a whole number height, found `{}`", s));
+        }
+
+        Ok(Self {
+            value: value.parse()?,
+            unit,
+        })
+    }
+}
+
+impl Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+/// A colour written as `#` followed by six lowercase hex digits.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct HexColor(pub [u8; 3]);
+
+impl FromStr for HexColor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let digits = s
+            .strip_prefix('#')
+            .filter(|d| d.len() == 6)
+            .filter(|d| d.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+            .ok_or_else(|| anyhow!("Expected a colour like `#a97842`, found `{}`", s))?;
+
+        let mut rgb = [0; 3];
+        for (i, channel) in rgb.iter_mut().enumerate() {
+            *channel = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16)?;
+        }
+
+        Ok(Self(rgb))
+    }
+}
+
+impl Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0[0], self.0[1], self.0[2])
+    }
+}
+
+/// One of the eye colours a passport may list.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum EyeColor {
+    Amb,
+    Blu,
+    Brn,
+    Gry,
+    Grn,
+    Hzl,
+    Oth,
+}
+
+impl FromStr for EyeColor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "amb" => Ok(Self::Amb),
+            "blu" => Ok(Self::Blu),
+            "brn" => Ok(Self::Brn),
+            "gry" => Ok(Self::Gry),
+            "grn" => Ok(Self::Grn),
+            "hzl" => Ok(Self::Hzl),
+            "oth" => Ok(Self::Oth),
+            _ => Err(anyhow!("Unknown eye colour `{}`", s)),
+        }
+    }
+}
+
+impl Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Amb => write!(f, "amb"),
+            Self::Blu => write!(f, "blu"),
+            Self::Brn => write!(f, "brn"),
+            Self::Gry => write!(f, "gry"),
+            Self::Grn => write!(f, "grn"),
+            Self::Hzl => write!(f, "hzl"),
+            Self::Oth => write!(f, "oth"),
+        }
+    }
+}
+
+/// A nine-digit passport ID. Leading zeros are significant.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct PassportId(String);
+
+impl PassportId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for PassportId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.len() != 9 || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(anyhow!("Expected a nine-digit passport ID, found `{}`", s));
+        }
+
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl Display for PassportId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A single passport record.
+///
+/// Every field is optional. The order of the fields and the whitespace between
+/// them is remembered, so that `Display` gives back the exact text the passport
+/// was parsed from.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Passport {
+    /// Birth year (`byr`)
+    pub birth_year: Option<Value<Year>>,
+    /// Issue year (`iyr`)
+    pub issue_year: Option<Value<Year>>,
+    /// Expiration year (`eyr`)
+    pub expiration_year: Option<Value<Year>>,
+    /// Height (`hgt`)
+    pub height: Option<Value<Height>>,
+    /// Hair color (`hcl`)
+    pub hair_color: Option<Value<HexColor>>,
+    /// Eye color (`ecl`)
+    pub eye_color: Option<Value<EyeColor>>,
+    /// Passport ID (`pid`)
+    pub passport_id: Option<Value<PassportId>>,
+    /// Country ID (`cid`)
+    pub country_id: Option<String>,
+    /// Any fields with keys not listed above, in the order they appeared
+    pub unknown: Vec<(String, String)>,
+    /// Each field's key, followed by the whitespace written after it
+    layout: Vec<(String, String)>,
+}
+
+impl Passport {
+    /// Parse a single record, as returned by [`records`].
+    ///
+    /// Malformed tokens and repeated keys are errors. Values that aren't in
+    /// their canonical form are kept as [`Value::Raw`].
+    pub fn parse(record: &str) -> Result<Self> {
+        let mut passport = Self::default();
+        let mut fields = fields(record).peekable();
+
+        while let Some(field) = fields.next() {
+            let field = field?;
+
+            let value_end = field.offset + field.key.len() + 1 + field.value.len();
+            let separator = match fields.peek() {
+                Some(Ok(next)) => &record[value_end..next.offset],
+                _ => &record[value_end..],
+            };
+
+            if passport.get(field.key).is_some() {
+                return Err(anyhow!(
+                    "Field `{}` appears more than once (again at byte {})",
+                    field.key,
+                    field.offset,
+                ));
+            }
+
+            match field.key {
+                "byr" => passport.birth_year = Some(Value::parse(field.value)),
+                "iyr" => passport.issue_year = Some(Value::parse(field.value)),
+                "eyr" => passport.expiration_year = Some(Value::parse(field.value)),
+                "hgt" => passport.height = Some(Value::parse(field.value)),
+                "hcl" => passport.hair_color = Some(Value::parse(field.value)),
+                "ecl" => passport.eye_color = Some(Value::parse(field.value)),
+                "pid" => passport.passport_id = Some(Value::parse(field.value)),
+                "cid" => passport.country_id = Some(field.value.to_string()),
+                key => passport
+                    .unknown
+                    .push((key.to_string(), field.value.to_string())),
+            }
+
+            passport
+                .layout
+                .push((field.key.to_string(), separator.to_string()));
+        }
+
+        Ok(passport)
+    }
+
+    /// Build a passport out of `(key, value)` pairs, laid out on a single line.
+    pub fn from_fields<'a, I>(fields: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut record = String::new();
+
+        for (key, value) in fields {
+            if key.contains(':') || key.contains(char::is_whitespace) {
+                return Err(anyhow!("Invalid field key `{}`", key));
+            }
+
+            if value.contains(char::is_whitespace) {
+                return Err(anyhow!(
+                    "Value `{}` of field `{}` contains whitespace",
+                    value,
+                    key
+                ));
+            }
+
+            if !record.is_empty() {
+                record.push(' ');
+            }
+
+            record.push_str(key);
+            record.push(':');
+            record.push_str(value);
+        }
+
+        Self::parse(&record)
+    }
+
+    /// Get the text of a field's value by its key.
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "byr" => self.birth_year.as_ref().map(Value::to_string),
+            "iyr" => self.issue_year.as_ref().map(Value::to_string),
+            "eyr" => self.expiration_year.as_ref().map(Value::to_string),
+            "hgt" => self.height.as_ref().map(Value::to_string),
+            "hcl" => self.hair_color.as_ref().map(Value::to_string),
+            "ecl" => self.eye_color.as_ref().map(Value::to_string),
+            "pid" => self.passport_id.as_ref().map(Value::to_string),
+            "cid" => self.country_id.clone(),
+            key => self
+                .unknown
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone()),
+        }
+    }
+
+    /// If all fields *except for country id* are present, the passport has
+    /// all its required fields.
+    pub fn has_required_fields(&self) -> bool {
+        self.validate(Validation::PRESENCE)
+    }
+
+    /// If all fields *except for country id* are present and hold valid
+    /// values, the passport is valid.
+    pub fn is_valid(&self) -> bool {
+        self.validate(Validation::VALUES)
+    }
+
+    /// Check the passport at some level of strictness.
+    pub fn validate(&self, validation: Validation) -> bool {
+        let has_fields = self.birth_year.is_some()
+            && self.issue_year.is_some()
+            && self.expiration_year.is_some()
+            && self.height.is_some()
+            && self.hair_color.is_some()
+            && self.eye_color.is_some()
+            && self.passport_id.is_some()
+            && (validation.north_pole_credentials || self.country_id.is_some());
+
+        match validation.level {
+            Level::Presence => has_fields,
+            Level::Values => has_fields && self.has_valid_values(),
+        }
+    }
+
+    /// Checks the values of every field except for country id. Missing fields
+    /// are invalid.
+    fn has_valid_values(&self) -> bool {
+        fn year_in(year: &Option<Value<Year>>, range: RangeInclusive<u16>) -> bool {
+            matches!(year, Some(Value::Parsed(Year(y))) if range.contains(y))
+        }
+
+        year_in(&self.birth_year, 1920..=2002)
+            && year_in(&self.issue_year, 2010..=2020)
+            && year_in(&self.expiration_year, 2020..=2030)
+            && match self.height {
+                Some(Value::Parsed(Height {
+                    value,
+                    unit: HeightUnit::Cm,
+                })) => (150..=193).contains(&value),
+                Some(Value::Parsed(Height {
+                    value,
+                    unit: HeightUnit::In,
+                })) => (59..=76).contains(&value),
+                _ => false,
+            }
+            && matches!(self.hair_color, Some(Value::Parsed(_)))
+            && matches!(self.eye_color, Some(Value::Parsed(_)))
+            && matches!(self.passport_id, Some(Value::Parsed(_)))
+    }
+}
+
+/// How thoroughly a passport's fields are checked.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Level {
+    /// Required fields only need to be present (part 1).
+    Presence,
+    /// Required fields must be present and hold valid values (part 2).
+    Values,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Presence => f.pad("presence"),
+            Self::Values => f.pad("values"),
+        }
+    }
+}
+
+/// A strictness level to validate passports at.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Validation {
+    pub level: Level,
+    /// If `true`, North Pole credentials (which look just like passports but
+    /// have no country id) are accepted.
+    pub north_pole_credentials: bool,
+}
+
+impl Validation {
+    /// Presence of required fields only, accepting North Pole credentials.
+    pub const PRESENCE: Self = Self {
+        level: Level::Presence,
+        north_pole_credentials: true,
+    };
+
+    /// Presence and values of required fields, accepting North Pole credentials.
+    pub const VALUES: Self = Self {
+        level: Level::Values,
+        north_pole_credentials: true,
+    };
+
+    /// Every combination of level and North Pole credential handling, from
+    /// least to most strict.
+    pub const ALL: [Self; 4] = [
+        Self::PRESENCE,
+        Self {
+            north_pole_credentials: false,
+            ..Self::PRESENCE
+        },
+        Self::VALUES,
+        Self {
+            north_pole_credentials: false,
+            ..Self::VALUES
+        },
+    ];
+}
+
+/// Write passports out as a batch, separating them with blank lines.
+pub fn write_batch<W: Write>(passports: &[Passport], mut writer: W) -> Result<()> {
+    for (i, passport) in passports.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+
+        writeln!(writer, "{}", passport)?;
+    }
+
+    Ok(())
+}
+
+impl Display for Passport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut written = Vec::new();
+        let mut pending_separator = "";
+
+        for (key, separator) in &self.layout {
+            if let Some(value) = self.get(key) {
+                write!(f, "{}{}:{}", pending_separator, key, value)?;
+                written.push(key.as_str());
+                pending_separator = separator;
+            }
+        }
+
+        // Fields that were set after parsing go at the end.
+        for key in KEYS
+            .iter()
+            .copied()
+            .chain(self.unknown.iter().map(|(k, _)| k.as_str()))
+        {
+            if !written.contains(&key) {
+                if let Some(value) = self.get(key) {
+                    let separator = if written.is_empty() { "" } else { " " };
+                    write!(f, "{}{}:{}", separator, key, value)?;
+                    written.push(key);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
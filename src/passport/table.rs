@@ -0,0 +1,137 @@
+//! Conversion of passports to and from tabular CSV and JSON data.
+//!
+//! Each passport becomes one row (or JSON object) with a column per known
+//! field. Fields with unknown keys are collected into the `other` column as
+//! space-separated `key:value` pairs. The `valid` column is computed on export
+//! and ignored on import.
+
+use super::{fields, Passport};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// A single passport as a flat table row.
+#[derive(Debug, Eq, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Row {
+    pub byr: Option<String>,
+    pub iyr: Option<String>,
+    pub eyr: Option<String>,
+    pub hgt: Option<String>,
+    pub hcl: Option<String>,
+    pub ecl: Option<String>,
+    pub pid: Option<String>,
+    pub cid: Option<String>,
+    pub other: Option<String>,
+    #[serde(skip_deserializing)]
+    pub valid: bool,
+}
+
+impl From<&Passport> for Row {
+    fn from(passport: &Passport) -> Self {
+        let other = passport
+            .unknown
+            .iter()
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            byr: passport.get("byr"),
+            iyr: passport.get("iyr"),
+            eyr: passport.get("eyr"),
+            hgt: passport.get("hgt"),
+            hcl: passport.get("hcl"),
+            ecl: passport.get("ecl"),
+            pid: passport.get("pid"),
+            cid: passport.get("cid"),
+            other: Some(other).filter(|o| !o.is_empty()),
+            valid: passport.is_valid(),
+        }
+    }
+}
+
+impl Row {
+    /// Turn the row back into a passport, with its fields in column order.
+    pub fn to_passport(&self) -> Result<Passport> {
+        let known = [
+            ("byr", &self.byr),
+            ("iyr", &self.iyr),
+            ("eyr", &self.eyr),
+            ("hgt", &self.hgt),
+            ("hcl", &self.hcl),
+            ("ecl", &self.ecl),
+            ("pid", &self.pid),
+            ("cid", &self.cid),
+        ];
+
+        let mut pairs = known
+            .iter()
+            .filter_map(|(key, value)| Some((*key, value.as_deref()?)))
+            .filter(|(_, value)| !value.is_empty())
+            .collect::<Vec<_>>();
+
+        if let Some(other) = &self.other {
+            for field in fields(other) {
+                let field = field.context("Could not read the `other` column")?;
+                pairs.push((field.key, field.value));
+            }
+        }
+
+        Passport::from_fields(pairs)
+    }
+}
+
+/// Write passports as CSV, with a header row.
+pub fn write_csv<W: Write>(passports: &[Passport], writer: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    for passport in passports {
+        writer.serialize(Row::from(passport))?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Write passports as a pretty-printed JSON array of objects.
+pub fn write_json<W: Write>(passports: &[Passport], mut writer: W) -> Result<()> {
+    let rows = passports.iter().map(Row::from).collect::<Vec<_>>();
+
+    serde_json::to_writer_pretty(&mut writer, &rows)?;
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+/// Read passports from CSV with a header row. Missing columns and empty cells
+/// are treated as absent fields.
+pub fn read_csv<R: Read>(reader: R) -> Result<Vec<Passport>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut passports = Vec::new();
+
+    for (i, row) in reader.deserialize::<Row>().enumerate() {
+        let passport = row
+            .map_err(anyhow::Error::from)
+            .and_then(|row| row.to_passport())
+            .with_context(|| format!("Could not read passport from CSV row {}", i + 1))?;
+
+        passports.push(passport);
+    }
+
+    Ok(passports)
+}
+
+/// Read passports from a JSON array of objects.
+pub fn read_json<R: Read>(reader: R) -> Result<Vec<Passport>> {
+    let rows: Vec<Row> = serde_json::from_reader(reader).context("Could not parse JSON")?;
+
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.to_passport()
+                .with_context(|| format!("Could not read passport from JSON object {}", i + 1))
+        })
+        .collect()
+}
@@ -0,0 +1,397 @@
+//! Boarding passes and seats, as used on day 5.
+//!
+//! A boarding pass like `FBFBBFFRLR` is just a binary number: `F` and `L` are
+//! `0` bits, `B` and `R` are `1` bits. The first bits are the row and the last
+//! bits are the column. How many bits there are, which characters are used and
+//! how a seat's ID is worked out all depend on the aircraft's [`Layout`].
+
+pub mod generate;
+pub mod map;
+
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// The arrangement of seats in an aircraft, and how its boarding passes are
+/// written.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Layout {
+    rows: u32,
+    cols: u32,
+    /// Characters for the (lower, upper) half of the remaining rows
+    row_chars: (char, char),
+    /// Characters for the (lower, upper) half of the remaining columns
+    col_chars: (char, char),
+    /// A seat's ID is `row * row_multiplier + col`
+    row_multiplier: u64,
+}
+
+impl Default for Layout {
+    /// The 128x8 layout from the puzzle, with `F`/`B` for rows, `L`/`R` for
+    /// columns and seat IDs of `row * 8 + col`.
+    fn default() -> Self {
+        Self {
+            rows: 128,
+            cols: 8,
+            row_chars: ('F', 'B'),
+            col_chars: ('L', 'R'),
+            row_multiplier: 8,
+        }
+    }
+}
+
+impl Layout {
+    /// Create a layout with the given number of rows and columns, which must
+    /// both be powers of two.
+    ///
+    /// Boarding passes use `F`/`B` for rows and `L`/`R` for columns, and seat
+    /// IDs are `row * cols + col`.
+    pub fn new(rows: u32, cols: u32) -> Result<Self> {
+        Self {
+            rows,
+            cols,
+            row_multiplier: cols as u64,
+            ..Self::default()
+        }
+        .validated()
+    }
+
+    /// Use different characters for the lower and upper halves of the rows.
+    pub fn with_row_chars(self, lower: char, upper: char) -> Result<Self> {
+        Self {
+            row_chars: (lower, upper),
+            ..self
+        }
+        .validated()
+    }
+
+    /// Use different characters for the lower and upper halves of the columns.
+    pub fn with_col_chars(self, lower: char, upper: char) -> Result<Self> {
+        Self {
+            col_chars: (lower, upper),
+            ..self
+        }
+        .validated()
+    }
+
+    /// Use `row * row_multiplier + col` as the seat ID formula. The multiplier
+    /// must be at least the number of columns, so that every seat gets its own
+    /// ID.
+    pub fn with_row_multiplier(self, row_multiplier: u64) -> Result<Self> {
+        Self {
+            row_multiplier,
+            ..self
+        }
+        .validated()
+    }
+
+    fn validated(self) -> Result<Self> {
+        if !self.rows.is_power_of_two() || !self.cols.is_power_of_two() {
+            return Err(anyhow!(
+                "Layout must have a power of two rows and columns, found {}x{}",
+                self.rows,
+                self.cols,
+            ));
+        }
+
+        if self.row_chars.0 == self.row_chars.1 || self.col_chars.0 == self.col_chars.1 {
+            return Err(anyhow!(
+                "Layout must use different characters for lower and upper halves, found {:?} and {:?}",
+                self.row_chars,
+                self.col_chars,
+            ));
+        }
+
+        if self.row_multiplier < self.cols as u64 {
+            return Err(anyhow!(
+                "Row multiplier {} is smaller than the number of columns {}",
+                self.row_multiplier,
+                self.cols,
+            ));
+        }
+
+        (self.rows as u64 - 1)
+            .checked_mul(self.row_multiplier)
+            .and_then(|id| id.checked_add(self.cols as u64 - 1))
+            .ok_or_else(|| anyhow!("Seat IDs in a {}x{} layout overflow", self.rows, self.cols))?;
+
+        Ok(self)
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    /// The number of characters used for the row in a boarding pass.
+    pub fn row_bits(&self) -> u32 {
+        self.rows.trailing_zeros()
+    }
+
+    /// The number of characters used for the column in a boarding pass.
+    pub fn col_bits(&self) -> u32 {
+        self.cols.trailing_zeros()
+    }
+
+    /// The total number of seats.
+    pub fn num_seats(&self) -> u64 {
+        self.rows as u64 * self.cols as u64
+    }
+
+    /// Create a seat from its row and column.
+    pub fn seat(&self, row: u32, col: u32) -> Result<Seat> {
+        if row >= self.rows || col >= self.cols {
+            return Err(anyhow!(
+                "Seat at row {}, column {} is outside of the {}x{} cabin",
+                row,
+                col,
+                self.rows,
+                self.cols,
+            ));
+        }
+
+        Ok(Seat { row, col })
+    }
+
+    /// Create a seat from its seat ID.
+    pub fn seat_from_id(&self, id: u64) -> Result<Seat> {
+        let row = id / self.row_multiplier;
+        let col = id % self.row_multiplier;
+
+        if row >= self.rows as u64 || col >= self.cols as u64 {
+            return Err(anyhow!("Seat ID {} is outside of the cabin", id));
+        }
+
+        Ok(Seat {
+            row: row as u32,
+            col: col as u32,
+        })
+    }
+
+    /// A seat's ID.
+    pub fn id(&self, seat: Seat) -> u64 {
+        seat.row as u64 * self.row_multiplier + seat.col as u64
+    }
+
+    /// Parse a seat from a boarding pass.
+    pub fn parse(&self, pass: &str) -> Result<Seat> {
+        let row_bits = self.row_bits() as usize;
+        let col_bits = self.col_bits() as usize;
+        let len = pass.chars().count();
+
+        if len != row_bits + col_bits {
+            return Err(anyhow!(
+                "Boarding pass {} is the wrong length. Expected length of {}, found {}",
+                pass,
+                row_bits + col_bits,
+                len,
+            ));
+        }
+
+        let mut row = 0;
+        let mut col = 0;
+
+        for (i, c) in pass.chars().enumerate() {
+            let (chars, value) = if i < row_bits {
+                (self.row_chars, &mut row)
+            } else {
+                (self.col_chars, &mut col)
+            };
+
+            let bit = if c == chars.0 {
+                0
+            } else if c == chars.1 {
+                1
+            } else {
+                return Err(anyhow!(
+                    "Expected character '{}' or '{}' in boarding pass {} at position {}, found {}",
+                    chars.0,
+                    chars.1,
+                    pass,
+                    i + 1,
+                    c,
+                ));
+            };
+
+            *value = (*value << 1) | bit;
+        }
+
+        self.seat(row, col)
+    }
+
+    /// Encode a seat as a boarding pass.
+    pub fn encode(&self, seat: Seat) -> String {
+        let row = (0..self.row_bits()).rev().map(|bit| {
+            if seat.row >> bit & 1 == 0 {
+                self.row_chars.0
+            } else {
+                self.row_chars.1
+            }
+        });
+
+        let col = (0..self.col_bits()).rev().map(|bit| {
+            if seat.col >> bit & 1 == 0 {
+                self.col_chars.0
+            } else {
+                self.col_chars.1
+            }
+        });
+
+        row.chain(col).collect()
+    }
+}
+
+impl FromStr for Layout {
+    type Err = anyhow::Error;
+
+    /// Parse a layout written as `<rows>x<cols>`, optionally followed by `/`
+    /// and the four lower/upper row and column characters, e.g. `128x8/FBLR`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (size, chars) = match s.split_once('/') {
+            Some((size, chars)) => (size, Some(chars)),
+            None => (s, None),
+        };
+
+        let (rows, cols) = size
+            .split_once('x')
+            .ok_or_else(|| anyhow!("Expected a layout like `128x8`, found `{}`", s))?;
+        let layout = Self::new(rows.parse()?, cols.parse()?)?;
+
+        match chars.map(|c| c.chars().collect::<Vec<_>>()).as_deref() {
+            None => Ok(layout),
+            Some(&[row_lo, row_hi, col_lo, col_hi]) => layout
+                .with_row_chars(row_lo, row_hi)?
+                .with_col_chars(col_lo, col_hi),
+            Some(_) => Err(anyhow!(
+                "Expected four layout characters like `FBLR`, found `{}`",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{}/{}{}{}{}",
+            self.rows,
+            self.cols,
+            self.row_chars.0,
+            self.row_chars.1,
+            self.col_chars.0,
+            self.col_chars.1,
+        )
+    }
+}
+
+/// A seat, identified by its row and column. Methods on `Seat` itself use the
+/// default [`Layout`].
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+pub struct Seat {
+    pub row: u32,
+    pub col: u32,
+}
+
+impl Seat {
+    /// Create a seat from its row and column.
+    pub fn new(row: u32, col: u32) -> Result<Self> {
+        Layout::default().seat(row, col)
+    }
+
+    /// Create a seat from its seat ID.
+    pub fn from_id(id: u64) -> Result<Self> {
+        Layout::default().seat_from_id(id)
+    }
+
+    /// The seat's ID, which is the boarding pass read as a binary number.
+    pub fn id(&self) -> u64 {
+        Layout::default().id(*self)
+    }
+}
+
+impl FromStr for Seat {
+    type Err = anyhow::Error;
+
+    /// Parse a seat from a 10-character boarding pass.
+    ///
+    /// The first 7 characters must be either "F" or "B".
+    ///
+    /// The last 3 characters must be either "L" or "R".
+    fn from_str(pass: &str) -> Result<Self> {
+        Layout::default().parse(pass)
+    }
+}
+
+impl Display for Seat {
+    /// Encode the seat as a boarding pass, e.g. `FBFBBFFRLR`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Layout::default().encode(*self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validated() {
+        assert!(Layout::new(128, 8).is_ok());
+        assert!(Layout::new(100, 8).is_err());
+        assert!(Layout::new(128, 0).is_err());
+        assert!(Layout::default().with_row_chars('F', 'F').is_err());
+        assert!(Layout::default().with_row_multiplier(7).is_err());
+        assert!(Layout::new(2, 2)
+            .unwrap()
+            .with_row_multiplier(u64::MAX)
+            .is_err());
+        assert!(Layout::new(1 << 31, 1 << 31).is_ok());
+    }
+
+    #[test]
+    fn layout_from_str() {
+        assert_eq!("128x8/FBLR".parse::<Layout>().unwrap(), Layout::default());
+        assert_eq!("128x8".parse::<Layout>().unwrap(), Layout::default());
+        assert_eq!(
+            "16x4/01ab".parse::<Layout>().unwrap().to_string(),
+            "16x4/01ab"
+        );
+
+        for bad in ["128", "128x8/FBL", "128x8/FBLRX", "axb", "128x6/FBLR"] {
+            assert!(bad.parse::<Layout>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn parse_and_encode_custom_layout() {
+        let layout = "16x4/01ab".parse::<Layout>().unwrap();
+
+        assert_eq!(layout.parse("1011ba").unwrap(), Seat { row: 11, col: 2 });
+        assert_eq!(layout.encode(Seat { row: 11, col: 2 }), "1011ba");
+        assert!(layout.parse("1011b").is_err());
+        assert!(layout.parse("1011bR").is_err());
+
+        for row in 0..16 {
+            for col in 0..4 {
+                let seat = layout.seat(row, col).unwrap();
+                assert_eq!(layout.parse(&layout.encode(seat)).unwrap(), seat);
+            }
+        }
+    }
+
+    #[test]
+    fn seat_from_id_with_gaps() {
+        let layout = Layout::new(4, 4).unwrap().with_row_multiplier(10).unwrap();
+
+        assert_eq!(layout.seat_from_id(23).unwrap(), Seat { row: 2, col: 3 });
+        assert_eq!(layout.id(Seat { row: 2, col: 3 }), 23);
+        // Columns 4 to 9 of each row don't exist
+        assert!(layout.seat_from_id(25).is_err());
+        assert!(layout.seat_from_id(40).is_err());
+    }
+}
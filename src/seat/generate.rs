@@ -0,0 +1,92 @@
+//! Building lists of seats to write out as boarding passes, e.g. to create
+//! day 5 style inputs.
+
+use super::{Layout, Seat};
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::{collections::HashSet, io::Read, ops::Range};
+
+/// A passenger and their seat, as read from a manifest.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Booking {
+    pub passenger: String,
+    pub seat: Seat,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestRow {
+    passenger: String,
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    row: Option<u32>,
+    #[serde(default)]
+    col: Option<u32>,
+}
+
+/// Read a CSV manifest with a header row. Every row needs a `passenger` column,
+/// and either an `id` column or both `row` and `col` columns.
+pub fn read_manifest<R: Read>(layout: &Layout, reader: R) -> Result<Vec<Booking>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut bookings = Vec::new();
+
+    for (i, row) in reader.deserialize::<ManifestRow>().enumerate() {
+        let booking = row
+            .map_err(anyhow::Error::from)
+            .and_then(|row| {
+                let seat = match (row.id, row.row, row.col) {
+                    (Some(id), None, None) => layout.seat_from_id(id)?,
+                    (None, Some(r), Some(c)) => layout.seat(r, c)?,
+                    _ => return Err(anyhow!("Expected either an `id` or a `row` and `col`")),
+                };
+
+                Ok(Booking {
+                    passenger: row.passenger,
+                    seat,
+                })
+            })
+            .with_context(|| format!("Could not read booking from manifest row {}", i + 1))?;
+
+        bookings.push(booking);
+    }
+
+    Ok(bookings)
+}
+
+/// Every seat in the given rows, from front to back.
+pub fn full_rows(layout: &Layout, rows: Range<u32>) -> Result<Vec<Seat>> {
+    if rows.end > layout.rows() {
+        return Err(anyhow!(
+            "Rows {} to {} don't fit in a cabin with {} rows",
+            rows.start,
+            rows.end,
+            layout.rows(),
+        ));
+    }
+
+    let cols = layout.cols();
+
+    Ok(rows
+        .flat_map(|row| (0..cols).map(move |col| Seat { row, col }))
+        .collect())
+}
+
+/// Seats whose neighbours (by ID) are both in the list, so that leaving one
+/// out makes a gap with both neighbours still in it.
+///
+/// This doesn't check that the gap would be the only one: if the list already
+/// has a seat missing between two others, leaving out one of these makes two.
+pub fn missable_seats(layout: &Layout, seats: &[Seat]) -> Vec<Seat> {
+    let ids = seats.iter().map(|s| layout.id(*s)).collect::<HashSet<_>>();
+
+    seats
+        .iter()
+        .copied()
+        .filter(|seat| {
+            let id = layout.id(*seat);
+
+            matches!(id.checked_sub(1), Some(prev) if ids.contains(&prev))
+                && matches!(id.checked_add(1), Some(next) if ids.contains(&next))
+        })
+        .collect()
+}
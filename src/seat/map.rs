@@ -0,0 +1,200 @@
+//! A map of which seats in a cabin are occupied.
+
+use super::{Layout, Seat};
+use anyhow::Result;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+    io::BufRead,
+    ops::Range,
+};
+
+/// The occupied seats of a cabin.
+///
+/// Going through every seat (e.g. in [`SeatMap::empty_seats`] or when
+/// rendering) takes time proportional to the size of the layout, so it's only
+/// practical for realistically sized aircraft.
+#[derive(Debug, Clone)]
+pub struct SeatMap {
+    layout: Layout,
+    occupied: BTreeSet<Seat>,
+}
+
+impl SeatMap {
+    /// Create a map of an empty cabin.
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            occupied: BTreeSet::new(),
+        }
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Mark a seat as occupied. Returns `false` if it already was.
+    pub fn occupy(&mut self, seat: Seat) -> bool {
+        self.occupied.insert(seat)
+    }
+
+    pub fn is_occupied(&self, seat: Seat) -> bool {
+        self.occupied.contains(&seat)
+    }
+
+    /// All occupied seats, from front to back.
+    pub fn occupied_seats(&self) -> impl Iterator<Item = Seat> + '_ {
+        self.occupied.iter().copied()
+    }
+
+    /// All empty seats, from front to back.
+    pub fn empty_seats(&self) -> impl Iterator<Item = Seat> + '_ {
+        self.rows_seats(0..self.layout.rows())
+            .filter(move |seat| !self.is_occupied(*seat))
+    }
+
+    /// Empty seats in rows that have at least one occupied seat, from front to
+    /// back. The wholly empty rows at the front and back are left out.
+    pub fn empty_seats_in_occupied_rows(&self) -> impl Iterator<Item = Seat> + '_ {
+        let rows = self.missing_front_rows().end..self.missing_back_rows().start;
+
+        self.rows_seats(rows)
+            .filter(move |seat| !self.is_occupied(*seat))
+    }
+
+    /// Empty seats where the seats with IDs one less and one more are both
+    /// occupied.
+    pub fn seats_between_occupied(&self) -> Vec<Seat> {
+        self.occupied
+            .iter()
+            .filter_map(|seat| {
+                let id = self.layout.id(*seat).checked_add(2)?;
+                let next = self.layout.seat_from_id(id).ok()?;
+                let between = self.layout.seat_from_id(id - 1).ok()?;
+
+                if self.is_occupied(next) && !self.is_occupied(between) {
+                    Some(between)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Look for your seat: the only empty seat whose neighbours (by ID) are
+    /// both occupied.
+    pub fn your_seat(&self) -> YourSeat {
+        let mut candidates = self.seats_between_occupied();
+
+        match candidates.len() {
+            0 => YourSeat::NotFound,
+            1 => YourSeat::Found(candidates.remove(0)),
+            _ => YourSeat::Ambiguous(candidates),
+        }
+    }
+
+    /// The rows at the front of the cabin with no occupied seats.
+    pub fn missing_front_rows(&self) -> Range<u32> {
+        match self.occupied.iter().next() {
+            Some(first) => 0..first.row,
+            None => 0..self.layout.rows(),
+        }
+    }
+
+    /// The rows at the back of the cabin with no occupied seats.
+    pub fn missing_back_rows(&self) -> Range<u32> {
+        match self.occupied.iter().next_back() {
+            Some(last) => last.row + 1..self.layout.rows(),
+            None => self.layout.rows()..self.layout.rows(),
+        }
+    }
+
+    fn rows_seats(&self, rows: Range<u32>) -> impl Iterator<Item = Seat> {
+        let cols = self.layout.cols();
+
+        rows.flat_map(move |row| (0..cols).map(move |col| Seat { row, col }))
+    }
+}
+
+impl Display for SeatMap {
+    /// Render the cabin as a grid with one line per row, front row first.
+    /// Occupied seats are shown as `#` and empty seats as `.`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (self.layout.rows() - 1).to_string().len();
+
+        for row in 0..self.layout.rows() {
+            write!(f, "{:>width$} ", row, width = width)?;
+
+            for col in 0..self.layout.cols() {
+                let occupied = self.is_occupied(Seat { row, col });
+                write!(f, "{}", if occupied { '#' } else { '.' })?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of looking for your seat.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum YourSeat {
+    /// Exactly one seat could be yours.
+    Found(Seat),
+    /// No seat could be yours.
+    NotFound,
+    /// Several seats could be yours.
+    Ambiguous(Vec<Seat>),
+}
+
+/// A list of boarding passes, read into a [`SeatMap`].
+#[derive(Debug)]
+pub struct Passes {
+    pub seat_map: SeatMap,
+    /// Seats that appear on more than one boarding pass, with the (1-based)
+    /// line numbers of every pass for that seat.
+    pub duplicates: Vec<(Seat, Vec<usize>)>,
+    /// Lines that aren't boarding passes for the layout, with their (1-based)
+    /// line numbers and the reason why.
+    pub invalid: Vec<(usize, anyhow::Error)>,
+}
+
+impl Passes {
+    /// Read one boarding pass per line, skipping blank lines. Duplicate and
+    /// invalid passes are collected rather than treated as errors; only I/O
+    /// errors are returned.
+    pub fn read<R: BufRead>(layout: Layout, reader: R) -> Result<Self> {
+        let mut seat_map = SeatMap::new(layout);
+        let mut line_nums = BTreeMap::<Seat, Vec<usize>>::new();
+        let mut invalid = Vec::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match seat_map.layout.parse(line) {
+                Ok(seat) => {
+                    seat_map.occupy(seat);
+                    line_nums.entry(seat).or_default().push(i + 1);
+                }
+                Err(e) => invalid.push((i + 1, e)),
+            }
+        }
+
+        let duplicates = line_nums
+            .into_iter()
+            .filter(|(_, line_nums)| line_nums.len() > 1)
+            .collect();
+
+        Ok(Self {
+            seat_map,
+            duplicates,
+            invalid,
+        })
+    }
+}